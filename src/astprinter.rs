@@ -1,11 +1,20 @@
 use crate::expr;
 use crate::value::Value;
 
+#[allow(dead_code)] // Debugging aid, not wired into the pipeline.
 pub struct AstPrinter;
 
 impl expr::Visitor for AstPrinter {
     type Result = String;
 
+    fn visit_assign(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Assign { name, value, depth: _ } = expr {
+            self.parenthesize(&format!("= {}", name.lexeme), &[value])
+        } else {
+            unreachable!()
+        }
+    }
+
     fn visit_binary(&mut self, expr: &expr::Expr) -> Self::Result {
         if let expr::Expr::Binary { left, operator, right } = expr {
             self.parenthesize(&operator.lexeme, &[left, right])
@@ -14,6 +23,41 @@ impl expr::Visitor for AstPrinter {
         }
     }
 
+    fn visit_call(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Call { callee, paren: _, arguments } = expr {
+            let mut exprs: Vec<&expr::Expr> = vec![callee];
+            exprs.extend(arguments.iter());
+            self.parenthesize("call", &exprs)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_compound(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Compound { target, operator, value, postfix } = expr {
+            let name: String = if *postfix { format!("postfix {}", operator.lexeme) } else { operator.lexeme.clone() };
+            self.parenthesize(&name, &[target, value])
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_conditional(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Conditional { condition, then_branch, else_branch } = expr {
+            self.parenthesize("?:", &[condition, then_branch, else_branch])
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_get(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Get { object, name } = expr {
+            self.parenthesize(&format!(". {}", name.lexeme), &[object])
+        } else {
+            unreachable!()
+        }
+    }
+
     fn visit_grouping(&mut self, expr: &expr::Expr) -> Self::Result {
         if let expr::Expr::Grouping { expression } = expr {
             self.parenthesize("group", &[expression])
@@ -22,12 +66,59 @@ impl expr::Visitor for AstPrinter {
         }
     }
 
+    fn visit_index(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Index { object, bracket: _, index } = expr {
+            self.parenthesize("[]", &[object, index])
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_indexset(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::IndexSet { object, bracket: _, index, value } = expr {
+            self.parenthesize("[]=", &[object, index, value])
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_interpolation(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Interpolation { parts } = expr {
+            let parts: Vec<&expr::Expr> = parts.iter().collect();
+            self.parenthesize("interpolate", &parts)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_lambda(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Lambda { keyword: _, params, body: _ } = expr {
+            let params: Vec<&str> = params.iter().map(|param| param.lexeme.as_str()).collect();
+            format!("(lambda ({}))", params.join(" "))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_list(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::List { elements } = expr {
+            let elements: Vec<&expr::Expr> = elements.iter().collect();
+            self.parenthesize("list", &elements)
+        } else {
+            unreachable!()
+        }
+    }
+
     fn visit_literal(&mut self, expr: &expr::Expr) -> Self::Result {
         if let expr::Expr::Literal { value } = expr {
             match value {
-                Value::Number(n) => n.to_string(),
+                Value::Int(_) | Value::Number(_) => value.to_string(),
                 Value::Str(s) => format!("\"{}\"", s),
                 Value::Bool(b) => b.to_string(),
+                Value::List(_) | Value::Map(_) | Value::Native(_) | Value::Range(_, _) => value.to_string(),
+                Value::Function(f) => format!("{:?}", f),
+                Value::Class(c) => format!("{:?}", c),
+                Value::Instance(i) => format!("{:?}", i.borrow()),
                 Value::Null => "null".to_string(),
             }
         } else {
@@ -35,6 +126,64 @@ impl expr::Visitor for AstPrinter {
         }
     }
 
+    fn visit_map(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Map { brace: _, keys, values } = expr {
+            let mut entries: Vec<&expr::Expr> = vec![];
+            for (key, value) in keys.iter().zip(values) {
+                entries.push(key);
+                entries.push(value);
+            }
+            self.parenthesize("map", &entries)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_match(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Match { keyword: _, subject, arms } = expr {
+            let mut builder: String = format!("(match {}", subject.accept(self));
+            for arm in arms {
+                builder.push_str(&format!(" (case {}", arm.pattern));
+                if let Some(guard) = &arm.guard {
+                    builder.push_str(&format!(" if {}", guard.accept(self)));
+                }
+                builder.push_str(&format!(" {})", arm.body.accept(self)));
+            }
+            builder.push(')');
+            builder
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_logical(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Logical { left, operator, right } = expr {
+            self.parenthesize(&operator.lexeme, &[left, right])
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_set(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Set { object, name, value } = expr {
+            self.parenthesize(&format!("= . {}", name.lexeme), &[object, value])
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_super(&mut self, expr: &expr::Expr) -> Self::Result {
+        if let expr::Expr::Super { keyword: _, method, depth: _ } = expr {
+            format!("(super {})", method.lexeme)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_this(&mut self, _expr: &expr::Expr) -> Self::Result {
+        String::from("this")
+    }
+
     fn visit_unary(&mut self, expr: &expr::Expr) -> Self::Result {
         if let expr::Expr::Unary { operator, right } = expr {
             self.parenthesize(&operator.lexeme, &[right])
@@ -44,7 +193,7 @@ impl expr::Visitor for AstPrinter {
     }
 
     fn visit_variable(&mut self, expr: &expr::Expr) -> Self::Result {
-        if let expr::Expr::Variable { name } = expr {
+        if let expr::Expr::Variable { name, depth: _ } = expr {
             format!("var {}", name)
         } else {
             unreachable!()
@@ -52,12 +201,13 @@ impl expr::Visitor for AstPrinter {
     }
 }
 
+#[allow(dead_code)]
 impl AstPrinter {
     pub fn print(&mut self, expr: &expr::Expr) -> String {
         expr.accept(self)
     }
 
-    fn parenthesize(&mut self, name: &str, exprs: &[&Box<expr::Expr>]) -> String {
+    fn parenthesize(&mut self, name: &str, exprs: &[&expr::Expr]) -> String {
         let mut builder = String::new();
         builder.push('(');
         builder.push_str(name);
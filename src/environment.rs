@@ -1,28 +1,119 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 use crate::error::RuntimeError;
 use crate::value::Value;
 use crate::token::Token;
 
 pub struct Environment {
-    pub values: HashMap<String, Value>
+    pub values: HashMap<String, Value>,
+    pub enclosing: Option<Rc<RefCell<Environment>>>,
+    // Names bound with `const`. The resolver rejects assignments to these up
+    // front; the check here covers what it can't see, like later REPL lines.
+    constants: HashSet<String>,
 }
 
 impl Environment {
     pub fn new() -> Self {
         Self {
-            values: HashMap::new()
+            values: HashMap::new(),
+            enclosing: None,
+            constants: HashSet::new(),
         }
     }
 
-    pub fn get(&mut self, name: &Token) -> Result<Value, RuntimeError> {
-        if self.values.contains_key(&name.lexeme) {
-            Ok(self.values.get(&name.lexeme).unwrap().clone())
-        } else {
-            Err(RuntimeError::new(("Undefined variable: '".to_string() + name.lexeme.as_str() + "'.").as_str(), name.line))
+    pub fn new_enclosing(enclosing: Rc<RefCell<Environment>>) -> Self {
+        Self {
+            values: HashMap::new(),
+            enclosing: Some(enclosing),
+            constants: HashSet::new(),
+        }
+    }
+
+    pub fn get(&self, name: &Token) -> Result<Value, RuntimeError> {
+        if let Some(value) = self.values.get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow().get(name),
+            None => Err(Self::undefined(name))
+        }
+    }
+
+    pub fn assign(&mut self, name: &Token, value: Value) -> Result<(), RuntimeError> {
+        if let Some(slot) = self.values.get_mut(&name.lexeme) {
+            if self.constants.contains(&name.lexeme) {
+                return Err(Self::constant(name));
+            }
+
+            *slot = value;
+            return Ok(());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+            None => Err(Self::undefined(name))
+        }
+    }
+
+    pub fn get_at(environment: &Rc<RefCell<Environment>>, distance: usize, name: &Token) -> Result<Value, RuntimeError> {
+        Self::lookup_at(environment, distance, &name.lexeme)
+            .ok_or_else(|| Self::undefined(name))
+    }
+
+    pub fn lookup_at(environment: &Rc<RefCell<Environment>>, distance: usize, name: &str) -> Option<Value> {
+        Self::ancestor(environment, distance).borrow().values.get(name).cloned()
+    }
+
+    pub fn assign_at(environment: &Rc<RefCell<Environment>>, distance: usize, name: &Token, value: Value) -> Result<(), RuntimeError> {
+        let ancestor: Rc<RefCell<Environment>> = Self::ancestor(environment, distance);
+        let mut ancestor = ancestor.borrow_mut();
+        if ancestor.constants.contains(&name.lexeme) {
+            return Err(Self::constant(name));
+        }
+
+        match ancestor.values.get_mut(&name.lexeme) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            },
+            None => Err(Self::undefined(name))
         }
     }
 
+    // Walks a fixed number of hops up the chain, as measured by the resolver.
+    fn ancestor(environment: &Rc<RefCell<Environment>>, distance: usize) -> Rc<RefCell<Environment>> {
+        let mut current: Rc<RefCell<Environment>> = environment.clone();
+        for _ in 0..distance {
+            let enclosing: Rc<RefCell<Environment>> = current.borrow().enclosing.clone()
+                .expect("Resolved depth exceeds the scope chain");
+            current = enclosing;
+        }
+
+        current
+    }
+
+    fn undefined(name: &Token) -> RuntimeError {
+        RuntimeError::new(("Undefined variable: '".to_string() + name.lexeme.as_str() + "'.").as_str(), name.line)
+    }
+
+    fn constant(name: &Token) -> RuntimeError {
+        RuntimeError::new(format!("Can't assign to constant '{}'.", name.lexeme).as_str(), name.line)
+    }
+
+    pub fn is_constant(&self, name: &str) -> bool {
+        self.constants.contains(name)
+    }
+
+    // A fresh binding replaces any earlier one, constness included.
     pub fn define(&mut self, name: String, value: Value) {
+        self.constants.remove(&name);
+        self.values.insert(name, value);
+    }
+
+    pub fn define_constant(&mut self, name: String, value: Value) {
+        self.constants.insert(name.clone());
         self.values.insert(name, value);
     }
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
 use std::fmt;
 use std::error::Error;
 
+use crate::value::Value;
+
 #[macro_export]
 macro_rules! define_error {
     ($name:ident) => {
@@ -35,14 +37,25 @@ define_error!(ScanError);
 // Required for the Error trait
 pub struct RuntimeError {
     pub message: String,
-    pub line: usize
+    pub line: usize,
+    // Set when the error came from a `throw`, so `catch` gets the value back unchanged.
+    pub thrown: Option<Value>,
 }
 
 impl RuntimeError {
     pub fn new(message: &str, line: usize) -> Self {
         RuntimeError {
             message: message.to_string(),
-            line: line,
+            line,
+            thrown: None,
+        }
+    }
+
+    pub fn throw(value: Value, line: usize) -> Self {
+        RuntimeError {
+            message: format!("Uncaught exception: {}", value),
+            line,
+            thrown: Some(value),
         }
     }
 }
@@ -1,21 +1,117 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use crate::pattern::MatchArm;
+use crate::stmt::Stmt;
 use crate::token::Token;
 use crate::value::Value;
 #[derive(Clone)]
 pub enum Expr {
+	Assign {
+		name: Token,
+		value: Box<Expr>,
+		depth: Cell<Option<usize>>,
+	},
+
 	Binary {
 		left: Box<Expr>,
 		operator: Token,
 		right: Box<Expr>,
 	},
 
+	Call {
+		callee: Box<Expr>,
+		paren: Token,
+		arguments: Vec<Expr>,
+	},
+
+	Compound {
+		target: Box<Expr>,
+		operator: Token,
+		value: Box<Expr>,
+		postfix: bool,
+	},
+
+	Conditional {
+		condition: Box<Expr>,
+		then_branch: Box<Expr>,
+		else_branch: Box<Expr>,
+	},
+
+	Get {
+		object: Box<Expr>,
+		name: Token,
+	},
+
 	Grouping {
 		expression: Box<Expr>,
 	},
 
+	Index {
+		object: Box<Expr>,
+		bracket: Token,
+		index: Box<Expr>,
+	},
+
+	IndexSet {
+		object: Box<Expr>,
+		bracket: Token,
+		index: Box<Expr>,
+		value: Box<Expr>,
+	},
+
+	Interpolation {
+		parts: Vec<Expr>,
+	},
+
+	Lambda {
+		keyword: Token,
+		params: Vec<Token>,
+		body: Rc<Vec<Stmt>>,
+	},
+
+	List {
+		elements: Vec<Expr>,
+	},
+
 	Literal {
 		value: Value,
 	},
 
+	Map {
+		brace: Token,
+		keys: Vec<Expr>,
+		values: Vec<Expr>,
+	},
+
+	Match {
+		keyword: Token,
+		subject: Box<Expr>,
+		arms: Vec<MatchArm>,
+	},
+
+	Logical {
+		left: Box<Expr>,
+		operator: Token,
+		right: Box<Expr>,
+	},
+
+	Set {
+		object: Box<Expr>,
+		name: Token,
+		value: Box<Expr>,
+	},
+
+	Super {
+		keyword: Token,
+		method: Token,
+		depth: Cell<Option<usize>>,
+	},
+
+	This {
+		keyword: Token,
+		depth: Cell<Option<usize>>,
+	},
+
 	Unary {
 		operator: Token,
 		right: Box<Expr>,
@@ -23,15 +119,32 @@ pub enum Expr {
 
 	Variable {
 		name: Token,
+		depth: Cell<Option<usize>>,
 	},
 }
 
 pub trait Visitor {
 	type Result;
 
+	fn visit_assign(&mut self, expr: &Expr) -> Self::Result;
 	fn visit_binary(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_call(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_compound(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_conditional(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_get(&mut self, expr: &Expr) -> Self::Result;
 	fn visit_grouping(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_index(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_indexset(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_interpolation(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_lambda(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_list(&mut self, expr: &Expr) -> Self::Result;
 	fn visit_literal(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_map(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_match(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_logical(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_set(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_super(&mut self, expr: &Expr) -> Self::Result;
+	fn visit_this(&mut self, expr: &Expr) -> Self::Result;
 	fn visit_unary(&mut self, expr: &Expr) -> Self::Result;
 	fn visit_variable(&mut self, expr: &Expr) -> Self::Result;
 }
@@ -39,19 +152,67 @@ pub trait Visitor {
 impl Expr {
 	pub fn accept<V: Visitor>(&self, visitor: &mut V) -> V::Result {
 		match self {
+			Expr::Assign {name: _, value: _, depth: _,  } => {
+				visitor.visit_assign(self)
+			}
 			Expr::Binary {left: _, operator: _, right: _,  } => {
 				visitor.visit_binary(self)
 			}
+			Expr::Call {callee: _, paren: _, arguments: _,  } => {
+				visitor.visit_call(self)
+			}
+			Expr::Compound {target: _, operator: _, value: _, postfix: _,  } => {
+				visitor.visit_compound(self)
+			}
+			Expr::Conditional {condition: _, then_branch: _, else_branch: _,  } => {
+				visitor.visit_conditional(self)
+			}
+			Expr::Get {object: _, name: _,  } => {
+				visitor.visit_get(self)
+			}
 			Expr::Grouping {expression: _,  } => {
 				visitor.visit_grouping(self)
 			}
+			Expr::Index {object: _, bracket: _, index: _,  } => {
+				visitor.visit_index(self)
+			}
+			Expr::IndexSet {object: _, bracket: _, index: _, value: _,  } => {
+				visitor.visit_indexset(self)
+			}
+			Expr::Interpolation {parts: _,  } => {
+				visitor.visit_interpolation(self)
+			}
+			Expr::Lambda {keyword: _, params: _, body: _,  } => {
+				visitor.visit_lambda(self)
+			}
+			Expr::List {elements: _,  } => {
+				visitor.visit_list(self)
+			}
 			Expr::Literal {value: _,  } => {
 				visitor.visit_literal(self)
 			}
+			Expr::Map {brace: _, keys: _, values: _,  } => {
+				visitor.visit_map(self)
+			}
+			Expr::Match {keyword: _, subject: _, arms: _,  } => {
+				visitor.visit_match(self)
+			}
+			Expr::Logical {left: _, operator: _, right: _,  } => {
+				visitor.visit_logical(self)
+			}
+			Expr::Set {object: _, name: _, value: _,  } => {
+				visitor.visit_set(self)
+			}
+			Expr::Super {keyword: _, method: _, depth: _,  } => {
+				visitor.visit_super(self)
+			}
+			Expr::This {keyword: _, depth: _,  } => {
+				visitor.visit_this(self)
+			}
 			Expr::Unary {operator: _, right: _,  } => {
 				visitor.visit_unary(self)
 			}
-			Expr::Variable {name: _,  } => {
+			Expr::Variable {name: _, depth: _,  } => {
 				visitor.visit_variable(self)
 			}
 		}
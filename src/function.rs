@@ -0,0 +1,75 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::environment::Environment;
+use crate::error::RuntimeError;
+use crate::interpreter::{Flow, Interpreter};
+use crate::stmt::Stmt;
+use crate::token::Token;
+use crate::value::Value;
+
+pub struct Function {
+    pub name: Token,
+    pub params: Vec<Token>,
+    pub body: Rc<Vec<Stmt>>,
+    pub closure: Rc<RefCell<Environment>>,
+    pub is_initializer: bool,
+}
+
+impl Function {
+    pub fn new(name: Token, params: Vec<Token>, body: Rc<Vec<Stmt>>, closure: Rc<RefCell<Environment>>, is_initializer: bool) -> Self {
+        Self {
+            name,
+            params,
+            body,
+            closure,
+            is_initializer,
+        }
+    }
+
+    // Wraps the closure in a scope where `this` is the given instance.
+    pub fn bind(&self, instance: Value) -> Function {
+        let mut environment: Environment = Environment::new_enclosing(self.closure.clone());
+        environment.define(String::from("this"), instance);
+
+        Function::new(self.name.clone(), self.params.clone(), self.body.clone(), Rc::new(RefCell::new(environment)), self.is_initializer)
+    }
+
+    pub fn arity(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+        let mut environment: Environment = Environment::new_enclosing(self.closure.clone());
+        for (param, argument) in self.params.iter().zip(arguments) {
+            environment.define(param.lexeme.clone(), argument);
+        }
+
+        let flow: Flow = interpreter.execute_block(&self.body, Rc::new(RefCell::new(environment)))?;
+
+        // Initializers always hand back the instance, even on an early `return;`.
+        if self.is_initializer {
+            return Ok(Environment::lookup_at(&self.closure, 0, "this").unwrap_or(Value::Null));
+        }
+
+        match flow {
+            Flow::Return(value) => Ok(value),
+            // The resolver keeps `break` and `continue` from escaping a function body.
+            Flow::Normal | Flow::Break(_) | Flow::Continue(_) => Ok(Value::Null),
+        }
+    }
+}
+
+// Functions are compared by identity, two declarations are never equal.
+impl PartialEq for Function {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<fn {}>", self.name.lexeme)
+    }
+}
\ No newline at end of file
@@ -1,61 +1,744 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::class::{Class, Instance};
+use crate::environment::Environment;
 use crate::expr::{self, Expr};
+use crate::function::Function;
+use crate::map::{Map, MapKey};
+use crate::pattern::{MatchArm, Pattern};
+use crate::stmt::{self, Stmt};
 use crate::token::Token;
-use crate::token_type::{self, TokenType};
+use crate::token_type::TokenType;
 use crate::error::RuntimeError;
 use crate::value::Value;
 use super::Sapphire;
 
+// How a statement finished, so `return`, `break` and `continue` can unwind
+// to their call or loop without being treated as an error. A loop label is
+// carried along when one was given.
+pub enum Flow {
+    Normal,
+    Return(Value),
+    Break(Option<String>),
+    Continue(Option<String>),
+}
+
+// Where a `for ... in` loop is up to. Lists are read live so pushes during the
+// loop are seen; maps and strings are snapshotted up front.
+enum Iteration {
+    List(Rc<RefCell<Vec<Value>>>, usize),
+    Items(std::vec::IntoIter<Vec<Value>>),
+    Range(std::ops::Range<i64>),
+    Instance(Rc<RefCell<Instance>>),
+}
+
+// Deep enough for ordinary recursion, shallow enough that runaway recursion
+// is reported as a catchable error well before the native stack runs out.
+const MAX_CALL_DEPTH: usize = 1000;
+
 pub struct Interpreter<'a> {
-    pub main: &'a mut Sapphire
+    pub main: &'a mut Sapphire,
+    environment: Rc<RefCell<Environment>>,
+    // Calls currently in progress, checked against `MAX_CALL_DEPTH`.
+    call_depth: usize,
 }
 
 impl<'a> Interpreter<'a> {
     pub fn new(main: &'a mut Sapphire) -> Self {
+        let environment: Rc<RefCell<Environment>> = main.globals.clone();
+
         Self {
-            main: main,
+            main,
+            environment,
+            call_depth: 0,
+        }
+    }
+
+    pub fn interpret(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            match self.execute(statement) {
+                Ok(_) => (),
+                Err(error) => {
+                    self.main.runtime_error(error);
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn execute(&mut self, statement: &Stmt) -> Result<Flow, RuntimeError> {
+        statement.accept(self)
+    }
+
+    pub fn execute_block(&mut self, statements: &[Stmt], environment: Rc<RefCell<Environment>>) -> Result<Flow, RuntimeError> {
+        let previous: Rc<RefCell<Environment>> = std::mem::replace(&mut self.environment, environment);
+        let result: Result<Flow, RuntimeError> = self.execute_statements(statements);
+        self.environment = previous;
+
+        result
+    }
+
+    fn execute_statements(&mut self, statements: &[Stmt]) -> Result<Flow, RuntimeError> {
+        for statement in statements {
+            match self.execute(statement)? {
+                Flow::Normal => (),
+                flow => return Ok(flow)
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    fn look_up_variable(&mut self, name: &Token, depth: Option<usize>) -> Result<Value, RuntimeError> {
+        match depth {
+            Some(distance) => Environment::get_at(&self.environment, distance, name),
+            None => self.main.globals.borrow().get(name)
         }
     }
 
-    pub fn interpret(&mut self, expression: &Expr) {
-        let result: Result<Value, RuntimeError> = self.evaluate(expression);
+    pub fn call_value(&mut self, callee: Value, arguments: Vec<Value>, line: usize) -> Result<Value, RuntimeError> {
+        let arity: usize = match &callee {
+            Value::Function(function) => function.arity(),
+            Value::Native(native) => native.arity,
+            Value::Class(class) => class.arity(),
+            _ => return Err(RuntimeError::new("TypeError - Can only call functions and classes.", line))
+        };
 
-        match result {
-            Ok(val) => {
-                println!("{:?}", val);
+        if arguments.len() != arity {
+            return Err(RuntimeError::new(format!("ArityError - Expected {} arguments but got {}.", arity, arguments.len()).as_str(), line));
+        }
+
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(RuntimeError::new(format!("StackOverflowError - Maximum call depth of {} exceeded.", MAX_CALL_DEPTH).as_str(), line));
+        }
+
+        self.call_depth += 1;
+        let result: Result<Value, RuntimeError> = self.call_checked(callee, arguments, line);
+        self.call_depth -= 1;
+
+        result
+    }
+
+    fn call_checked(&mut self, callee: Value, arguments: Vec<Value>, line: usize) -> Result<Value, RuntimeError> {
+        match callee {
+            Value::Function(function) => function.call(self, arguments),
+            Value::Native(native) => native.call(self, arguments, line),
+            Value::Class(class) => {
+                let instance: Value = Value::Instance(Rc::new(RefCell::new(Instance::new(class.clone()))));
+                if let Some(initializer) = class.find_method("init") {
+                    initializer.bind(instance.clone()).call(self, arguments)?;
+                }
+
+                Ok(instance)
             },
+            _ => unreachable!()
+        }
+    }
+
+    // Converts a (possibly negative) index into a position inside `length`.
+    fn list_index(&mut self, index: &Value, length: usize, line: usize) -> Result<usize, RuntimeError> {
+        let Value::Int(int) = index else {
+            return Err(RuntimeError::new(format!("TypeError - List index must be an Int, got '{}'.", index.type_name()).as_str(), line));
+        };
 
-            Err(error) => {
-                self.main.runtime_error(error);
+        let position: i64 = if *int < 0 { int + length as i64 } else { *int };
+        if position < 0 || position >= length as i64 {
+            return Err(RuntimeError::new(format!("IndexError - List index {} out of range for length {}.", int, length).as_str(), line));
+        }
+
+        Ok(position as usize)
+    }
+
+    pub fn map_key(&mut self, key: &Value, line: usize) -> Result<MapKey, RuntimeError> {
+        MapKey::from_value(key).ok_or_else(|| {
+            RuntimeError::new(format!("TypeError - Map keys must be Str, Int, Number or Bool, got '{}'.", key.type_name()).as_str(), line)
+        })
+    }
+
+    fn as_float(value: &Value) -> f64 {
+        match value {
+            Value::Int(int) => *int as f64,
+            Value::Number(num) => *num,
+            _ => unreachable!()
+        }
+    }
+
+    fn binary_values(&mut self, left: Value, operator: &Token, right: Value) -> Result<Value, RuntimeError> {
+        match operator.token_type {
+            TokenType::DoubleDotLess => return match (&left, &right) {
+                (Value::Int(start), Value::Int(end)) => Ok(Value::Range(*start, *end)),
+                _ => Err(RuntimeError::new(format!("TypeError - Range bounds must be Int, got '{}' and '{}'.", left.type_name(), right.type_name()).as_str(), operator.line))
+            },
+            TokenType::DoubleDot => return Ok(Value::Str(left.to_string() + right.to_string().as_str())),
+            TokenType::BangEqual => return Ok(Value::Bool(!self.is_equal(&left, &right))),
+            TokenType::EqualEqual => return Ok(Value::Bool(self.is_equal(&left, &right))),
+            _ => ()
+        }
+
+        match (&left, &right) {
+            (Value::Int(il), Value::Int(ir)) => self.int_binary(*il, *ir, operator),
+            // Mixing an Int with a Number promotes the Int to a float.
+            (Value::Int(_) | Value::Number(_), Value::Int(_) | Value::Number(_)) => {
+                let (nl, nr) = (Self::as_float(&left), Self::as_float(&right));
+                self.float_binary(nl, nr, operator)
+            },
+            (Value::Str(_), Value::Str(_)) => Err(RuntimeError::new("TypeError - Unsupported operation for Str", operator.line)),
+            _ => Err(RuntimeError::new("TypeError - Type mismatch for operands of binary operation.", operator.line))
+        }
+    }
+
+    fn index_get(&mut self, object: &Value, index: &Value, bracket: &Token) -> Result<Value, RuntimeError> {
+        match object {
+            Value::List(list) => {
+                let position: usize = self.list_index(index, list.borrow().len(), bracket.line)?;
+                Ok(list.borrow()[position].clone())
+            },
+            // Missing keys read as nil, so optional settings can fall back.
+            Value::Map(map) => {
+                let key: MapKey = self.map_key(index, bracket.line)?;
+                Ok(map.borrow().get(&key).cloned().unwrap_or(Value::Null))
+            },
+            _ => Err(RuntimeError::new(format!("TypeError - Can't index into '{}'.", object.type_name()).as_str(), bracket.line))
+        }
+    }
+
+    fn index_set(&mut self, object: &Value, index: &Value, bracket: &Token, value: Value) -> Result<Value, RuntimeError> {
+        match object {
+            Value::List(list) => {
+                let position: usize = self.list_index(index, list.borrow().len(), bracket.line)?;
+                list.borrow_mut()[position] = value.clone();
+                Ok(value)
+            },
+            Value::Map(map) => {
+                let key: MapKey = self.map_key(index, bracket.line)?;
+                map.borrow_mut().insert(key, value.clone());
+                Ok(value)
+            },
+            _ => Err(RuntimeError::new(format!("TypeError - Can't assign by index into '{}'.", object.type_name()).as_str(), bracket.line))
+        }
+    }
+
+    // `pairs` asks for two values per step: index and element, or key and value.
+    fn iteration(&mut self, iterable: Value, pairs: bool, line: usize) -> Result<Iteration, RuntimeError> {
+        match (iterable, pairs) {
+            (Value::List(list), _) => Ok(Iteration::List(list, 0)),
+            (Value::Map(map), false) => {
+                let keys: Vec<Vec<Value>> = map.borrow().iter().map(|(key, _)| vec![key.to_value()]).collect();
+                Ok(Iteration::Items(keys.into_iter()))
+            },
+            (Value::Map(map), true) => {
+                let entries: Vec<Vec<Value>> = map.borrow().iter().map(|(key, value)| vec![key.to_value(), value.clone()]).collect();
+                Ok(Iteration::Items(entries.into_iter()))
+            },
+            (Value::Str(str), false) => {
+                let characters: Vec<Vec<Value>> = str.chars().map(|character| vec![Value::Str(character.to_string())]).collect();
+                Ok(Iteration::Items(characters.into_iter()))
+            },
+            (Value::Range(start, end), false) => Ok(Iteration::Range(start..end)),
+            (Value::Instance(instance), false) => Ok(Iteration::Instance(instance)),
+            (iterable, true) => Err(RuntimeError::new(format!("TypeError - Can't iterate over '{}' with two loop variables.", iterable.type_name()).as_str(), line)),
+            (iterable, false) => Err(RuntimeError::new(format!("TypeError - Can't iterate over '{}'.", iterable.type_name()).as_str(), line))
+        }
+    }
+
+    // Instances iterate by calling their `next()` method until it returns nil.
+    fn iteration_next(&mut self, iteration: &mut Iteration, pairs: bool, keyword: &Token) -> Result<Option<Vec<Value>>, RuntimeError> {
+        match iteration {
+            Iteration::List(list, position) => {
+                let element: Option<Value> = list.borrow().get(*position).cloned();
+                let index: i64 = *position as i64;
+                *position += 1;
+
+                Ok(element.map(|element| if pairs { vec![Value::Int(index), element] } else { vec![element] }))
+            },
+            Iteration::Items(items) => Ok(items.next()),
+            Iteration::Range(range) => Ok(range.next().map(|int| vec![Value::Int(int)])),
+            Iteration::Instance(instance) => {
+                let name: Token = Token { lexeme: String::from("next"), ..keyword.clone() };
+                let next: Value = Instance::get(instance, &name)?;
+
+                match self.call_value(next, vec![], keyword.line)? {
+                    Value::Null => Ok(None),
+                    value => Ok(Some(vec![value]))
+                }
             }
         }
     }
 
+    // Tests `value` against `pattern`, defining its bindings in `environment`.
+    // A mismatch is described in the error so destructuring can report it.
+    fn match_pattern(&mut self, pattern: &Pattern, value: &Value, environment: &mut Environment) -> Result<(), String> {
+        match (pattern, value) {
+            (Pattern::Wildcard, _) => Ok(()),
+            (Pattern::Binding(name), _) => {
+                environment.define(name.lexeme.clone(), value.clone());
+                Ok(())
+            },
+            (Pattern::Literal(literal), _) => {
+                if self.is_equal(literal, value) {
+                    Ok(())
+                } else {
+                    Err(format!("Expected {}, got {}.", pattern, value))
+                }
+            },
+            (Pattern::List { elements, rest }, Value::List(list)) => {
+                let list: Vec<Value> = list.borrow().clone();
+                match rest {
+                    None if list.len() != elements.len() => {
+                        return Err(format!("Expected a List of {} elements, got {}.", elements.len(), list.len()));
+                    },
+                    Some(_) if list.len() < elements.len() => {
+                        return Err(format!("Expected a List of at least {} elements, got {}.", elements.len(), list.len()));
+                    },
+                    _ => ()
+                }
+
+                for (element, value) in elements.iter().zip(&list) {
+                    self.match_pattern(element, value, environment)?;
+                }
+
+                if let Some(rest) = rest {
+                    let remaining: Vec<Value> = list[elements.len()..].to_vec();
+                    self.match_pattern(rest, &Value::List(Rc::new(RefCell::new(remaining))), environment)?;
+                }
+
+                Ok(())
+            },
+            (Pattern::Map { entries }, Value::Map(map)) => {
+                for (key, entry) in entries {
+                    let found: Option<Value> = map.borrow().get(key).cloned();
+                    let Some(found) = found else {
+                        return Err(format!("Missing key '{}' in Map.", key.to_value()));
+                    };
+
+                    self.match_pattern(entry, &found, environment)?;
+                }
+
+                Ok(())
+            },
+            (Pattern::Or(alternatives), _) => {
+                for alternative in alternatives {
+                    if self.match_pattern(alternative, value, environment).is_ok() {
+                        return Ok(());
+                    }
+                }
+
+                Err(format!("Expected {}, got {}.", pattern, value))
+            },
+            (Pattern::List { .. }, _) => Err(format!("Expected a List, got '{}'.", value.type_name())),
+            (Pattern::Map { .. }, _) => Err(format!("Expected a Map, got '{}'.", value.type_name())),
+        }
+    }
+
+    // Runs an arm whose pattern matched; `None` means its guard turned it down.
+    fn match_arm(&mut self, arm: &MatchArm) -> Result<Option<Value>, RuntimeError> {
+        if let Some(guard) = &arm.guard {
+            let guard: Value = self.evaluate(guard)?;
+            if !self.is_truthy(&guard)? {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(self.evaluate(&arm.body)?))
+    }
+
+    // An unlabeled `break` or `continue` targets the innermost loop, a labeled one only if the names match.
+    fn targets_loop(target: &Option<String>, label: &Option<Token>) -> bool {
+        match (target, label) {
+            (None, _) => true,
+            (Some(target), Some(label)) => *target == label.lexeme,
+            (Some(_), None) => false,
+        }
+    }
+
+    fn int_binary(&mut self, il: i64, ir: i64, operator: &Token) -> Result<Value, RuntimeError> {
+        let checked: Option<i64> = match operator.token_type {
+            TokenType::Plus => il.checked_add(ir),
+            TokenType::Minus => il.checked_sub(ir),
+            TokenType::Star => il.checked_mul(ir),
+            TokenType::Slash | TokenType::Percent if ir == 0 => {
+                return Err(RuntimeError::new("ZeroDivisionError - Integer division by zero.", operator.line));
+            },
+            // Integer division truncates toward zero, and `%` agrees with it.
+            TokenType::Slash => il.checked_div(ir),
+            TokenType::Percent => il.checked_rem(ir),
+            // A negative exponent has no Int result, so it falls back to floats.
+            TokenType::StarStar if ir < 0 => return Ok(Value::Number((il as f64).powf(ir as f64))),
+            TokenType::StarStar => u32::try_from(ir).ok().and_then(|exponent| il.checked_pow(exponent)),
+
+            TokenType::Ampersand => return Ok(Value::Int(il & ir)),
+            TokenType::Pipe => return Ok(Value::Int(il | ir)),
+            TokenType::Caret => return Ok(Value::Int(il ^ ir)),
+            TokenType::LessLess | TokenType::GreaterGreater if !(0..64).contains(&ir) => {
+                return Err(RuntimeError::new(format!("OverflowError - Shift amount {} out of range 0..64.", ir).as_str(), operator.line));
+            },
+            TokenType::LessLess => Some(il << ir),
+            TokenType::GreaterGreater => Some(il >> ir),
+
+            TokenType::Greater => return Ok(Value::Bool(il > ir)),
+            TokenType::GreaterEqual => return Ok(Value::Bool(il >= ir)),
+            TokenType::Less => return Ok(Value::Bool(il < ir)),
+            TokenType::LessEqual => return Ok(Value::Bool(il <= ir)),
+
+            _ => return Err(RuntimeError::new("OperationError - Unsupported operand for binary operation on Int", operator.line))
+        };
+
+        checked.map(Value::Int).ok_or_else(|| {
+            RuntimeError::new(format!("OverflowError - Integer overflow in '{}'.", operator.lexeme).as_str(), operator.line)
+        })
+    }
+
+    fn float_binary(&mut self, nl: f64, nr: f64, operator: &Token) -> Result<Value, RuntimeError> {
+        match operator.token_type {
+            TokenType::Plus => Ok(Value::Number(nl + nr)),
+            TokenType::Minus => Ok(Value::Number(nl - nr)),
+            TokenType::Star => Ok(Value::Number(nl * nr)),
+            TokenType::Slash => Ok(Value::Number(nl / nr)),
+            TokenType::Percent => Ok(Value::Number(nl % nr)),
+            TokenType::StarStar => Ok(Value::Number(nl.powf(nr))),
+
+            TokenType::Ampersand | TokenType::Pipe | TokenType::Caret |
+                TokenType::LessLess | TokenType::GreaterGreater => {
+                Err(RuntimeError::new(format!("TypeError - Operands of '{}' must both be Int.", operator.lexeme).as_str(), operator.line))
+            },
+
+            TokenType::Greater => Ok(Value::Bool(nl > nr)),
+            TokenType::GreaterEqual => Ok(Value::Bool(nl >= nr)),
+            TokenType::Less => Ok(Value::Bool(nl < nr)),
+            TokenType::LessEqual => Ok(Value::Bool(nl <= nr)),
+
+            _ => Err(RuntimeError::new("OperationError - Unsupported operand for binary operation on Number", operator.line))
+        }
+    }
+
     pub fn evaluate(&mut self, expression: &Expr) -> Result<Value, RuntimeError> {
         expression.accept(self)
     }
 
     pub fn is_truthy(&mut self, value: &Value) -> Result<bool, RuntimeError> {
         match *value {
-            Value::Str(_) => Ok(true),
-            Value::Number(_) => Ok(true),
             Value::Bool(bool_value) => Ok(bool_value),
-            Value::Null => Ok(false)
+            Value::Null => Ok(false),
+            _ => Ok(true)
         }
     }
 
     pub fn is_equal(&mut self, val1: &Value, val2: &Value) -> bool {
+        self.is_equal_seen(val1, val2, &mut vec![])
+    }
+
+    // `comparing` holds the pairs of collections already being compared further
+    // up, so self-containing values are assumed equal there instead of recursing forever.
+    fn is_equal_seen(&mut self, val1: &Value, val2: &Value, comparing: &mut Vec<(*const (), *const ())>) -> bool {
         match (val1, val2) {
             (Value::Null, Value::Null) => true,
+            (Value::Int(i1), Value::Int(i2)) => i1 == i2,
             (Value::Number(n1), Value::Number(n2)) => n1 == n2,
+            (Value::Int(i), Value::Number(n)) | (Value::Number(n), Value::Int(i)) => {
+                n.fract() == 0.0 && (*i as f64) == *n && (*n as i64) == *i
+            },
             (Value::Str(s1), Value::Str(s2)) => s1 == s2,
             (Value::Bool(b1), Value::Bool(b2)) => b1 == b2,
+            (Value::List(l1), Value::List(l2)) => {
+                if Rc::ptr_eq(l1, l2) {
+                    return true;
+                }
+
+                let pair: (*const (), *const ()) = (Rc::as_ptr(l1) as *const (), Rc::as_ptr(l2) as *const ());
+                if comparing.contains(&pair) {
+                    return true;
+                }
+
+                comparing.push(pair);
+                let (l1, l2) = (l1.borrow(), l2.borrow());
+                let equal: bool = l1.len() == l2.len() && l1.iter().zip(l2.iter()).all(|(v1, v2)| self.is_equal_seen(v1, v2, comparing));
+                comparing.pop();
+                equal
+            },
+            (Value::Map(m1), Value::Map(m2)) => {
+                if Rc::ptr_eq(m1, m2) {
+                    return true;
+                }
+
+                let pair: (*const (), *const ()) = (Rc::as_ptr(m1) as *const (), Rc::as_ptr(m2) as *const ());
+                if comparing.contains(&pair) {
+                    return true;
+                }
+
+                comparing.push(pair);
+                let (m1, m2) = (m1.borrow(), m2.borrow());
+                let equal: bool = m1.len() == m2.len() && m1.iter().all(|(key, v1)| {
+                    m2.get(key).is_some_and(|v2| self.is_equal_seen(v1, v2, comparing))
+                });
+                comparing.pop();
+                equal
+            },
+            (Value::Function(f1), Value::Function(f2)) => Rc::ptr_eq(f1, f2),
+            (Value::Native(n1), Value::Native(n2)) => Rc::ptr_eq(n1, n2),
+            (Value::Class(c1), Value::Class(c2)) => Rc::ptr_eq(c1, c2),
+            (Value::Instance(i1), Value::Instance(i2)) => Rc::ptr_eq(i1, i2),
+            (Value::Range(s1, e1), Value::Range(s2, e2)) => s1 == s2 && e1 == e2,
 
             _ => false, // Catches all other combinations
         }
     }
 }
 
-impl<'a> expr::Visitor for Interpreter<'a> {
+impl stmt::Visitor for Interpreter<'_> {
+    type Result = Result<Flow, RuntimeError>;
+
+    fn visit_block(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Block { statements } = stmt {
+            let environment: Environment = Environment::new_enclosing(self.environment.clone());
+            self.execute_block(statements, Rc::new(RefCell::new(environment)))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_break(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Break { keyword: _, label } = stmt {
+            Ok(Flow::Break(label.as_ref().map(|label| label.lexeme.clone())))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_const(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Const { name, initializer } = stmt {
+            let value: Value = self.evaluate(initializer)?;
+            self.environment.borrow_mut().define_constant(name.lexeme.clone(), value);
+            Ok(Flow::Normal)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_continue(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Continue { keyword: _, label } = stmt {
+            Ok(Flow::Continue(label.as_ref().map(|label| label.lexeme.clone())))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_class(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Class { name, superclass, methods } = stmt {
+            let mut superclass_value: Option<Rc<Class>> = None;
+            if let Some(superclass) = superclass {
+                match self.evaluate(superclass)? {
+                    Value::Class(class) => superclass_value = Some(class),
+                    _ => return Err(RuntimeError::new("TypeError - Superclass must be a class.", name.line))
+                }
+            }
+
+            self.environment.borrow_mut().define(name.lexeme.clone(), Value::Null);
+
+            // Methods of a subclass close over an extra scope holding `super`.
+            let mut method_closure: Rc<RefCell<Environment>> = self.environment.clone();
+            if let Some(superclass) = &superclass_value {
+                let mut environment: Environment = Environment::new_enclosing(self.environment.clone());
+                environment.define(String::from("super"), Value::Class(superclass.clone()));
+                method_closure = Rc::new(RefCell::new(environment));
+            }
+
+            let mut class_methods: HashMap<String, Rc<Function>> = HashMap::new();
+            for method in methods {
+                if let Stmt::Function { name, params, body } = method {
+                    let is_initializer: bool = name.lexeme == "init";
+                    let function: Function = Function::new(name.clone(), params.clone(), body.clone(), method_closure.clone(), is_initializer);
+                    class_methods.insert(name.lexeme.clone(), Rc::new(function));
+                }
+            }
+
+            let class: Class = Class::new(name.lexeme.clone(), superclass_value, class_methods);
+            self.environment.borrow_mut().assign(name, Value::Class(Rc::new(class)))?;
+            Ok(Flow::Normal)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_destructure(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Destructure { keyword, pattern, initializer } = stmt {
+            let value: Value = self.evaluate(initializer)?;
+
+            let environment: Rc<RefCell<Environment>> = self.environment.clone();
+            if let Err(reason) = self.match_pattern(pattern, &value, &mut environment.borrow_mut()) {
+                return Err(RuntimeError::new(format!("PatternError - Can't destructure {}: {}", pattern, reason).as_str(), keyword.line));
+            }
+
+            Ok(Flow::Normal)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_expression(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Expression { expression } = stmt {
+            self.evaluate(expression)?;
+            Ok(Flow::Normal)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_forin(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::ForIn { label, names, keyword, iterable, body } = stmt {
+            let iterable: Value = self.evaluate(iterable)?;
+            let pairs: bool = names.len() == 2;
+            let mut iteration: Iteration = self.iteration(iterable, pairs, keyword.line)?;
+
+            while let Some(values) = self.iteration_next(&mut iteration, pairs, keyword)? {
+                // Each pass gets a fresh scope, so closures capture that pass's item.
+                let mut environment: Environment = Environment::new_enclosing(self.environment.clone());
+                for (name, value) in names.iter().zip(values) {
+                    environment.define(name.lexeme.clone(), value);
+                }
+
+                match self.execute_block(std::slice::from_ref(body.as_ref()), Rc::new(RefCell::new(environment)))? {
+                    Flow::Normal => (),
+                    Flow::Break(target) if Self::targets_loop(&target, label) => break,
+                    Flow::Continue(target) if Self::targets_loop(&target, label) => (),
+                    flow => return Ok(flow)
+                }
+            }
+
+            Ok(Flow::Normal)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_function(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Function { name, params, body } = stmt {
+            let function: Function = Function::new(name.clone(), params.clone(), body.clone(), self.environment.clone(), false);
+            self.environment.borrow_mut().define(name.lexeme.clone(), Value::Function(Rc::new(function)));
+            Ok(Flow::Normal)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_if(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::If { condition, then_branch, else_branch } = stmt {
+            let condition: Value = self.evaluate(condition)?;
+
+            if self.is_truthy(&condition)? {
+                self.execute(then_branch)
+            } else if let Some(else_branch) = else_branch {
+                self.execute(else_branch)
+            } else {
+                Ok(Flow::Normal)
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_print(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Print { expression } = stmt {
+            let value: Value = self.evaluate(expression)?;
+            println!("{}", value);
+            Ok(Flow::Normal)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_return(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Return { keyword: _, value } = stmt {
+            let value: Value = match value {
+                Some(value) => self.evaluate(value)?,
+                None => Value::Null
+            };
+
+            Ok(Flow::Return(value))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_throw(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Throw { keyword, value } = stmt {
+            let value: Value = self.evaluate(value)?;
+            Err(RuntimeError::throw(value, keyword.line))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_try(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Try { body, catch_name, catch_body, finally_body } = stmt {
+            let mut result: Result<Flow, RuntimeError> = self.execute_block(body, Rc::new(RefCell::new(Environment::new_enclosing(self.environment.clone()))));
+
+            if let (Err(error), Some(catch_name)) = (&result, catch_name) {
+                // Built-in errors, runaway recursion included, are caught as a map so scripts can inspect them.
+                let caught: Value = match &error.thrown {
+                    Some(value) => value.clone(),
+                    None => {
+                        let mut map: Map = Map::new();
+                        map.insert(MapKey::Str(String::from("message")), Value::Str(error.message.clone()));
+                        map.insert(MapKey::Str(String::from("line")), Value::Int(error.line as i64));
+                        Value::Map(Rc::new(RefCell::new(map)))
+                    }
+                };
+
+                let mut environment: Environment = Environment::new_enclosing(self.environment.clone());
+                environment.define(catch_name.lexeme.clone(), caught);
+                result = self.execute_block(catch_body, Rc::new(RefCell::new(environment)));
+            }
+
+            // `finally` always runs; an error or return inside it replaces the pending outcome.
+            match self.execute_block(finally_body, Rc::new(RefCell::new(Environment::new_enclosing(self.environment.clone()))))? {
+                Flow::Normal => result,
+                flow => Ok(flow)
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_var(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Var { name, initializer } = stmt {
+            let value: Value = self.evaluate(initializer)?;
+            self.environment.borrow_mut().define(name.lexeme.clone(), value);
+            Ok(Flow::Normal)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_while(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::While { label, condition, body, increment } = stmt {
+            loop {
+                let condition: Value = self.evaluate(condition)?;
+                if !self.is_truthy(&condition)? {
+                    break;
+                }
+
+                match self.execute(body)? {
+                    Flow::Normal => (),
+                    Flow::Break(target) if Self::targets_loop(&target, label) => break,
+                    Flow::Continue(target) if Self::targets_loop(&target, label) => (),
+                    flow => return Ok(flow)
+                }
+
+                if let Some(increment) = increment {
+                    self.evaluate(increment)?;
+                }
+            }
+
+            Ok(Flow::Normal)
+        } else {
+            unreachable!()
+        }
+    }
+}
+
+impl expr::Visitor for Interpreter<'_> {
     type Result = Result<Value, RuntimeError>;
 
     fn visit_literal(&mut self, expr: &Expr) -> Self::Result {
@@ -71,34 +754,294 @@ impl<'a> expr::Visitor for Interpreter<'a> {
             let result_left: Value = self.evaluate(left)?;
             let result_right: Value = self.evaluate(right)?;
 
-            if operator.token_type == TokenType::DoubleDot {
-                return Ok(Value::Str(result_left.to_string() + result_right.to_string().as_str()));
+            self.binary_values(result_left, operator, result_right)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_assign(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Assign { name, value, depth } = expr {
+            let value: Value = self.evaluate(value)?;
+
+            match depth.get() {
+                Some(distance) => Environment::assign_at(&self.environment, distance, name, value.clone())?,
+                None => self.main.globals.borrow_mut().assign(name, value.clone())?
             }
 
-            if let (Value::Number(nl), Value::Number(nr)) = (&result_left, &result_right) {
-                match operator.token_type {
-                    TokenType::Plus => Ok(Value::Number(nl + nr)),
-                    TokenType::Minus => Ok(Value::Number(nl - nr)),
-                    TokenType::Star => Ok(Value::Number(nl * nr)),
-                    TokenType::Slash => Ok(Value::Number(nl / nr)),
+            Ok(value)
+        } else {
+            unreachable!()
+        }
+    }
 
-                    TokenType::Greater => Ok(Value::Bool(nl > nr)),
-                    TokenType::GreaterEqual => Ok(Value::Bool(nl >= nr)),
-                    TokenType::Less => Ok(Value::Bool(nl < nr)),
-                    TokenType::LessEqual => Ok(Value::Bool(nl <= nr)),
+    fn visit_variable(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Variable { name, depth } = expr {
+            self.look_up_variable(name, depth.get())
+        } else {
+            unreachable!()
+        }
+    }
 
-                    TokenType::BangEqual => Ok(Value::Bool(nl != nr)),
-                    TokenType::EqualEqual => Ok(Value::Bool(nl == nr)),
+    fn visit_index(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Index { object, bracket, index } = expr {
+            let object: Value = self.evaluate(object)?;
+            let index: Value = self.evaluate(index)?;
 
-                    _ => Err(RuntimeError::new("OperationError - Unsupported operand for binary operation on Number", operator.line))
+            self.index_get(&object, &index, bracket)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_indexset(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::IndexSet { object, bracket, index, value } = expr {
+            let object: Value = self.evaluate(object)?;
+            let index: Value = self.evaluate(index)?;
+            let value: Value = self.evaluate(value)?;
+
+            self.index_set(&object, &index, bracket, value)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_compound(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Compound { target, operator, value, postfix } = expr {
+            // `x += y` and `x++` behave like `x = x + y` and `x = x + 1`, with
+            // errors reported as the plain operator.
+            let (token_type, lexeme) = match operator.token_type {
+                TokenType::PlusEqual | TokenType::PlusPlus => (TokenType::Plus, "+"),
+                TokenType::MinusEqual | TokenType::MinusMinus => (TokenType::Minus, "-"),
+                TokenType::StarEqual => (TokenType::Star, "*"),
+                TokenType::SlashEqual => (TokenType::Slash, "/"),
+                TokenType::DoubleDotEqual => (TokenType::DoubleDot, ".."),
+                _ => unreachable!()
+            };
+            let binary: Token = Token { token_type, lexeme: lexeme.to_string(), literal: Value::Null, line: operator.line };
+
+            // The target's object and index are evaluated once, before the right-hand side.
+            let (current, result): (Value, Value) = match target.as_ref() {
+                Expr::Variable { name, depth } => {
+                    let current: Value = self.look_up_variable(name, depth.get())?;
+                    let right: Value = self.evaluate(value)?;
+                    let result: Value = self.binary_values(current.clone(), &binary, right)?;
+
+                    match depth.get() {
+                        Some(distance) => Environment::assign_at(&self.environment, distance, name, result.clone())?,
+                        None => self.main.globals.borrow_mut().assign(name, result.clone())?
+                    }
+
+                    (current, result)
+                },
+                Expr::Get { object, name } => {
+                    let Value::Instance(instance) = self.evaluate(object)? else {
+                        return Err(RuntimeError::new("TypeError - Only instances have fields.", name.line));
+                    };
+
+                    let current: Value = Instance::get(&instance, name)?;
+                    let right: Value = self.evaluate(value)?;
+                    let result: Value = self.binary_values(current.clone(), &binary, right)?;
+                    instance.borrow_mut().set(name, result.clone());
+                    (current, result)
+                },
+                Expr::Index { object, bracket, index } => {
+                    let object: Value = self.evaluate(object)?;
+                    let index: Value = self.evaluate(index)?;
+
+                    let current: Value = self.index_get(&object, &index, bracket)?;
+                    let right: Value = self.evaluate(value)?;
+                    let result: Value = self.binary_values(current.clone(), &binary, right)?;
+                    (current, self.index_set(&object, &index, bracket, result)?)
+                },
+                _ => unreachable!()
+            };
+
+            Ok(if *postfix { current } else { result })
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_interpolation(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Interpolation { parts } = expr {
+            // Each part is stringified exactly as the `..` operator would.
+            let mut result: String = String::new();
+            for part in parts {
+                result += self.evaluate(part)?.to_string().as_str();
+            }
+
+            Ok(Value::Str(result))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_list(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::List { elements } = expr {
+            let mut values: Vec<Value> = vec![];
+            for element in elements {
+                values.push(self.evaluate(element)?);
+            }
+
+            Ok(Value::List(Rc::new(RefCell::new(values))))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_map(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Map { brace, keys, values } = expr {
+            let mut map: Map = Map::new();
+            for (key, value) in keys.iter().zip(values) {
+                let key: Value = self.evaluate(key)?;
+                let key: MapKey = self.map_key(&key, brace.line)?;
+                let value: Value = self.evaluate(value)?;
+                map.insert(key, value);
+            }
+
+            Ok(Value::Map(Rc::new(RefCell::new(map))))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_match(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Match { keyword, subject, arms } = expr {
+            let subject: Value = self.evaluate(subject)?;
+
+            for arm in arms {
+                let mut environment: Environment = Environment::new_enclosing(self.environment.clone());
+                if self.match_pattern(&arm.pattern, &subject, &mut environment).is_err() {
+                    continue;
                 }
-            } else {
-                if let (Value::Str(sl), Value::Str(sr)) = (&result_left, &result_right) {
-                    return Err(RuntimeError::new("TypeError - Unsupported operation for Str", operator.line));
+
+                let previous: Rc<RefCell<Environment>> = std::mem::replace(&mut self.environment, Rc::new(RefCell::new(environment)));
+                let result: Result<Option<Value>, RuntimeError> = self.match_arm(arm);
+                self.environment = previous;
+
+                if let Some(value) = result? {
+                    return Ok(value);
                 }
+            }
+
+            Err(RuntimeError::new(format!("MatchError - No arm matched {}.", subject).as_str(), keyword.line))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_logical(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Logical { left, operator, right } = expr {
+            let result_left: Value = self.evaluate(left)?;
+
+            if operator.token_type == TokenType::QuestionQuestion {
+                return match result_left {
+                    Value::Null => self.evaluate(right),
+                    _ => Ok(result_left)
+                };
+            }
+
+            let left_truthy: bool = self.is_truthy(&result_left)?;
+
+            // Short-circuit, handing back the operand that decided the result.
+            match operator.token_type {
+                TokenType::Or if left_truthy => Ok(result_left),
+                TokenType::And if !left_truthy => Ok(result_left),
+                _ => self.evaluate(right)
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_conditional(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Conditional { condition, then_branch, else_branch } = expr {
+            let condition: Value = self.evaluate(condition)?;
+
+            if self.is_truthy(&condition)? {
+                self.evaluate(then_branch)
+            } else {
+                self.evaluate(else_branch)
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_lambda(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Lambda { keyword, params, body } = expr {
+            let name: Token = Token { lexeme: String::from("lambda"), ..keyword.clone() };
+            let function: Function = Function::new(name, params.clone(), body.clone(), self.environment.clone(), false);
+            Ok(Value::Function(Rc::new(function)))
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_call(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Call { callee, paren, arguments } = expr {
+            let callee: Value = self.evaluate(callee)?;
 
-                Err(RuntimeError::new("TypeError - Type mismatch for operands of binary operation.", operator.line))
+            let mut argument_values: Vec<Value> = vec![];
+            for argument in arguments {
+                argument_values.push(self.evaluate(argument)?);
             }
+
+            self.call_value(callee, argument_values, paren.line)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_get(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Get { object, name } = expr {
+            match self.evaluate(object)? {
+                Value::Instance(instance) => Instance::get(&instance, name),
+                _ => Err(RuntimeError::new("TypeError - Only instances have properties.", name.line))
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_set(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Set { object, name, value } = expr {
+            let Value::Instance(instance) = self.evaluate(object)? else {
+                return Err(RuntimeError::new("TypeError - Only instances have fields.", name.line));
+            };
+
+            let value: Value = self.evaluate(value)?;
+            instance.borrow_mut().set(name, value.clone());
+            Ok(value)
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_super(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Super { keyword, method, depth } = expr {
+            let distance: usize = depth.get().expect("'super' was not resolved");
+            let superclass: Option<Value> = Environment::lookup_at(&self.environment, distance, "super");
+            // `this` always lives in the scope just inside the one holding `super`.
+            let object: Option<Value> = Environment::lookup_at(&self.environment, distance - 1, "this");
+
+            let (Some(Value::Class(superclass)), Some(object)) = (superclass, object) else {
+                return Err(RuntimeError::new("Invalid 'super' binding.", keyword.line));
+            };
+
+            match superclass.find_method(&method.lexeme) {
+                Some(found) => Ok(Value::Function(Rc::new(found.bind(object)))),
+                None => Err(RuntimeError::new(format!("Undefined property '{}'.", method.lexeme).as_str(), method.line))
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_this(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::This { keyword, depth } = expr {
+            self.look_up_variable(keyword, depth.get())
         } else {
             unreachable!()
         }
@@ -116,31 +1059,78 @@ impl<'a> expr::Visitor for Interpreter<'a> {
         if let Expr::Unary { operator, right } = expr {
             let result_right: Value = self.evaluate(right)?;
 
-            match result_right {
-                Value::Str(val) => return Err(RuntimeError::new("TypeError - Invalid type for unary: 'Str', operand must be Number", operator.line)),
-                Value::Null => return Err(RuntimeError::new("TypeError - Invalid type for unary: 'Null', operand must be Number", operator.line)),
-                _ => ()
-            }
-
-            match operator.token_type {
-                TokenType::Bang => {
-                    match result_right {
-                        Value::Bool(val) => Ok(Value::Bool(!self.is_truthy(&result_right)?)),
-                        Value::Number(val ) => Ok(Value::Bool(!self.is_truthy(&result_right)?)),
-                        _ => unreachable!()
-                    }
-                },
-                TokenType::Minus => {
-                    match result_right {
-                        Value::Bool(val) => Ok(Value::Bool(!val)),
-                        Value::Number(val ) => Ok(Value::Number(-val)),
-                        _ => unreachable!()
-                    }
-                }
-                _ => unreachable!()
+            match (&operator.token_type, &result_right) {
+                (TokenType::Bang, Value::Bool(_) | Value::Int(_) | Value::Number(_)) => Ok(Value::Bool(!self.is_truthy(&result_right)?)),
+                (TokenType::Minus, Value::Bool(val)) => Ok(Value::Bool(!val)),
+                (TokenType::Minus, Value::Int(val)) => val.checked_neg().map(Value::Int).ok_or_else(|| {
+                    RuntimeError::new("OverflowError - Integer overflow in '-'.", operator.line)
+                }),
+                (TokenType::Minus, Value::Number(val)) => Ok(Value::Number(-val)),
+                (TokenType::Tilde, Value::Int(val)) => Ok(Value::Int(!val)),
+                (TokenType::Tilde, _) => Err(RuntimeError::new(format!("TypeError - Invalid type for unary '~': '{}', operand must be Int", result_right.type_name()).as_str(), operator.line)),
+                _ => Err(RuntimeError::new(format!("TypeError - Invalid type for unary: '{}', operand must be Number", result_right.type_name()).as_str(), operator.line))
             }
         } else {
             unreachable!()
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Sapphire, INTERPRETER_STACK_SIZE};
+    use crate::value::Value;
+
+    // Runs `source` on a thread sized like the real interpreter's, handing
+    // back whether it hit a runtime error and the printed form of `global`.
+    fn run(source: &str, global: &str) -> (bool, Option<String>) {
+        let (source, global): (String, String) = (source.to_string(), global.to_string());
+
+        std::thread::Builder::new()
+            .stack_size(INTERPRETER_STACK_SIZE)
+            .spawn(move || {
+                let mut sapphire: Sapphire = Sapphire::new();
+                sapphire.run(source);
+
+                let value: Option<Value> = sapphire.globals.borrow().values.get(&global).cloned();
+                (sapphire.had_runtime_error, value.map(|value| value.to_string()))
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+
+    #[test]
+    fn runaway_recursion_is_caught_by_try() {
+        let (had_runtime_error, caught) = run(
+            "fn f(n) { return f(n + 1); } var caught = nil; try { f(0); } catch (e) { caught = e[\"message\"]; }",
+            "caught",
+        );
+
+        assert!(!had_runtime_error);
+        assert!(caught.is_some_and(|message| message.starts_with("StackOverflowError")));
+    }
+
+    #[test]
+    fn calls_work_again_after_catching_runaway_recursion() {
+        let (had_runtime_error, after) = run(
+            "fn f(n) { return f(n + 1); } fn g(n) { if (n == 0) return 0; return 1 + g(n - 1); } \
+             try { f(0); } catch (e) { } var after = g(900);",
+            "after",
+        );
+
+        assert!(!had_runtime_error);
+        assert_eq!(after.as_deref(), Some("900"));
+    }
+
+    #[test]
+    fn map_patterns_match_literal_keys() {
+        let (had_runtime_error, found) = run(
+            "var found = match {1: \"one\", -2: \"neg\", true: \"yes\"} { {1: a, -2: b, true: c} => a .. b .. c, _ => nil };",
+            "found",
+        );
+
+        assert!(!had_runtime_error);
+        assert_eq!(found.as_deref(), Some("onenegyes"));
+    }
 }
\ No newline at end of file
@@ -1,36 +1,53 @@
+use std::cell::RefCell;
 use std::env;
 use std::io::{self, Write};
 use std::fs;
+use std::rc::Rc;
 
+use crate::environment::Environment;
 use crate::error::RuntimeError;
-use crate::expr::Expr;
 use crate::interpreter::Interpreter;
 use crate::parser::Parser;
+use crate::resolver::Resolver;
+use crate::stmt::Stmt;
 use crate::token::Token;
 use crate::token_type::TokenType;
 use crate::scanner::Scanner;
-use crate::astprinter::AstPrinter;
 
 mod scanner;
 mod token;
 mod token_type;
 mod value;
 mod expr; 
+mod stmt;
 mod astprinter;
 mod parser;
+mod resolver;
 mod error;
+mod environment;
+mod class;
+mod function;
+mod native;
+mod map;
+mod pattern;
 mod interpreter;
 
 struct Sapphire {
     pub had_error: bool,
     pub had_runtime_error: bool,
+    // Kept here so globals survive between REPL lines.
+    pub globals: Rc<RefCell<Environment>>,
 }
 
 impl Sapphire {
     pub fn new() -> Self {
+        let mut globals: Environment = Environment::new();
+        native::define_natives(&mut globals);
+
         Self {
             had_error: false,
             had_runtime_error: false,
+            globals: Rc::new(RefCell::new(globals)),
         }
     }
 
@@ -47,19 +64,21 @@ impl Sapphire {
         }
 
         let mut parser: Parser = Parser::new(self, tokens);
+        let statements: Vec<Stmt> = parser.parse();
 
-        let mut expression: Expr;
-        match parser.parse() {
-            Ok(expr) => expression = expr.clone(),
-            _ => return
+        if self.had_error {
+            return;
         }
 
+        let mut resolver: Resolver = Resolver::new(self);
+        resolver.resolve(&statements);
+
         if self.had_error {
             return;
         }
 
         let mut interpreter: Interpreter = Interpreter::new(self);
-        interpreter.interpret(&expression);
+        interpreter.interpret(&statements);
     }
 
     pub fn runtime_error(&mut self, error: RuntimeError) {
@@ -84,11 +103,11 @@ impl Sapphire {
     }
     
     fn run_file(&mut self, filename: String) {
-        let mut contents = self.read_file_contents(filename);
+        let contents = self.read_file_contents(filename);
     
         match contents {
             Ok(contents) => self.run(contents),
-            Err(e) => println!("There was an error reading the file.")
+            Err(_) => println!("There was an error reading the file.")
         }
 
         if self.had_error {
@@ -120,19 +139,28 @@ impl Sapphire {
     }
 }
 
-fn main() -> std::io::Result<()> {
-    let mut sapphire: Sapphire = Sapphire::new();
+// The tree-walker recurses on the native stack, so it gets more room than the
+// main thread's default to reach the interpreter's own call-depth limit.
+const INTERPRETER_STACK_SIZE: usize = 256 * 1024 * 1024;
 
+fn main() -> std::io::Result<()> {
     let args: Vec<String> = env::args().collect();
     let args_len: usize = args.len();
 
-    if args_len > 2 {
-        println!("Usage: sapphire [file]");
-    } else if args_len == 2 {
-        sapphire.run_file(args[1].clone());
-    } else {
-        sapphire.run_prompt();
-    }
+    let interpreter: std::thread::JoinHandle<()> = std::thread::Builder::new()
+        .stack_size(INTERPRETER_STACK_SIZE)
+        .spawn(move || {
+            let mut sapphire: Sapphire = Sapphire::new();
+
+            if args_len > 2 {
+                println!("Usage: sapphire [file]");
+            } else if args_len == 2 {
+                sapphire.run_file(args[1].clone());
+            } else {
+                sapphire.run_prompt();
+            }
+        })?;
 
+    interpreter.join().expect("Interpreter thread panicked");
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use crate::value::Value;
+
+// The subset of values that can be used as map keys: those with a stable
+// notion of equality. Whole floats are folded into Int keys, since `1 == 1.0`;
+// other floats are stored by bit pattern with NaN rejected.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Str(String),
+    Int(i64),
+    Number(u64),
+    Bool(bool),
+}
+
+impl MapKey {
+    pub fn from_value(value: &Value) -> Option<MapKey> {
+        match value {
+            Value::Str(str) => Some(MapKey::Str(str.clone())),
+            Value::Int(int) => Some(MapKey::Int(*int)),
+            Value::Number(num) if num.fract() == 0.0 && num.abs() < i64::MAX as f64 => Some(MapKey::Int(*num as i64)),
+            Value::Number(num) if !num.is_nan() => Some(MapKey::Number(num.to_bits())),
+            Value::Bool(bool) => Some(MapKey::Bool(*bool)),
+            _ => None
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            MapKey::Str(str) => Value::Str(str.clone()),
+            MapKey::Int(int) => Value::Int(*int),
+            MapKey::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            MapKey::Bool(bool) => Value::Bool(*bool),
+        }
+    }
+}
+
+// A hash map that remembers insertion order, so iteration is deterministic.
+#[derive(Debug)]
+pub struct Map {
+    entries: Vec<(MapKey, Value)>,
+    positions: HashMap<MapKey, usize>,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![],
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<&Value> {
+        self.positions.get(key).map(|position| &self.entries[*position].1)
+    }
+
+    pub fn contains_key(&self, key: &MapKey) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    // Overwriting an existing key keeps its original position.
+    pub fn insert(&mut self, key: MapKey, value: Value) {
+        match self.positions.get(&key) {
+            Some(position) => self.entries[*position].1 = value,
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &MapKey) -> Option<Value> {
+        let position: usize = self.positions.remove(key)?;
+        let (_, value) = self.entries.remove(position);
+
+        for (_, later) in self.positions.iter_mut() {
+            if *later > position {
+                *later -= 1;
+            }
+        }
+
+        Some(value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(MapKey, Value)> {
+        self.entries.iter()
+    }
+}
+
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
\ No newline at end of file
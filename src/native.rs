@@ -0,0 +1,122 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::environment::Environment;
+use crate::error::RuntimeError;
+use crate::interpreter::Interpreter;
+use crate::map::MapKey;
+use crate::value::Value;
+
+// Receives the evaluated arguments and the line of the call.
+pub type NativeFn = fn(&mut Interpreter, Vec<Value>, usize) -> Result<Value, RuntimeError>;
+
+pub struct NativeFunction {
+    pub name: &'static str,
+    pub arity: usize,
+    pub function: NativeFn,
+}
+
+impl NativeFunction {
+    pub fn new(name: &'static str, arity: usize, function: NativeFn) -> Self {
+        Self {
+            name,
+            arity,
+            function,
+        }
+    }
+
+    pub fn call(&self, interpreter: &mut Interpreter, arguments: Vec<Value>, line: usize) -> Result<Value, RuntimeError> {
+        (self.function)(interpreter, arguments, line)
+    }
+}
+
+impl PartialEq for NativeFunction {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<native fn {}>", self.name)
+    }
+}
+
+pub fn define_natives(environment: &mut Environment) {
+    let natives: Vec<NativeFunction> = vec![
+        NativeFunction::new("len", 1, len),
+        NativeFunction::new("push", 2, push),
+        NativeFunction::new("pop", 1, pop),
+        NativeFunction::new("keys", 1, keys),
+        NativeFunction::new("has", 2, has),
+        NativeFunction::new("remove", 2, remove),
+    ];
+
+    for native in natives {
+        environment.define(native.name.to_string(), Value::Native(Rc::new(native)));
+    }
+}
+
+fn len(_interpreter: &mut Interpreter, arguments: Vec<Value>, line: usize) -> Result<Value, RuntimeError> {
+    match &arguments[0] {
+        Value::List(list) => Ok(Value::Int(list.borrow().len() as i64)),
+        Value::Map(map) => Ok(Value::Int(map.borrow().len() as i64)),
+        Value::Str(str) => Ok(Value::Int(str.chars().count() as i64)),
+        Value::Range(start, end) => Ok(Value::Int(end.saturating_sub(*start).max(0))),
+        other => Err(RuntimeError::new(format!("TypeError - len() expects a List, Map, Str or Range, got '{}'.", other.type_name()).as_str(), line))
+    }
+}
+
+fn push(_interpreter: &mut Interpreter, arguments: Vec<Value>, line: usize) -> Result<Value, RuntimeError> {
+    let mut arguments = arguments.into_iter();
+    let (Some(target), Some(value)) = (arguments.next(), arguments.next()) else {
+        unreachable!()
+    };
+
+    match target {
+        Value::List(list) => {
+            list.borrow_mut().push(value);
+            Ok(Value::Null)
+        },
+        other => Err(RuntimeError::new(format!("TypeError - push() expects a List, got '{}'.", other.type_name()).as_str(), line))
+    }
+}
+
+fn pop(_interpreter: &mut Interpreter, arguments: Vec<Value>, line: usize) -> Result<Value, RuntimeError> {
+    match &arguments[0] {
+        Value::List(list) => list.borrow_mut().pop()
+            .ok_or_else(|| RuntimeError::new("IndexError - pop() from an empty List.", line)),
+        other => Err(RuntimeError::new(format!("TypeError - pop() expects a List, got '{}'.", other.type_name()).as_str(), line))
+    }
+}
+
+fn keys(_interpreter: &mut Interpreter, arguments: Vec<Value>, line: usize) -> Result<Value, RuntimeError> {
+    match &arguments[0] {
+        Value::Map(map) => {
+            let keys: Vec<Value> = map.borrow().iter().map(|(key, _)| key.to_value()).collect();
+            Ok(Value::List(Rc::new(RefCell::new(keys))))
+        },
+        other => Err(RuntimeError::new(format!("TypeError - keys() expects a Map, got '{}'.", other.type_name()).as_str(), line))
+    }
+}
+
+fn has(interpreter: &mut Interpreter, arguments: Vec<Value>, line: usize) -> Result<Value, RuntimeError> {
+    match &arguments[0] {
+        Value::Map(map) => {
+            let key: MapKey = interpreter.map_key(&arguments[1], line)?;
+            Ok(Value::Bool(map.borrow().contains_key(&key)))
+        },
+        other => Err(RuntimeError::new(format!("TypeError - has() expects a Map, got '{}'.", other.type_name()).as_str(), line))
+    }
+}
+
+fn remove(interpreter: &mut Interpreter, arguments: Vec<Value>, line: usize) -> Result<Value, RuntimeError> {
+    match &arguments[0] {
+        Value::Map(map) => {
+            let key: MapKey = interpreter.map_key(&arguments[1], line)?;
+            Ok(map.borrow_mut().remove(&key).unwrap_or(Value::Null))
+        },
+        other => Err(RuntimeError::new(format!("TypeError - remove() expects a Map, got '{}'.", other.type_name()).as_str(), line))
+    }
+}
\ No newline at end of file
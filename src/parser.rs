@@ -1,10 +1,14 @@
-use std::error::Error;
+use std::cell::Cell;
+use std::rc::Rc;
 
 use crate::error::ParseError;
 use crate::token::Token;
 use crate::token_type::TokenType;
 use crate::value::Value;
 use crate::expr::Expr;
+use crate::map::MapKey;
+use crate::pattern::{MatchArm, Pattern};
+use crate::stmt::Stmt;
 use super::Sapphire;
 
 pub struct Parser<'a> {
@@ -16,9 +20,9 @@ pub struct Parser<'a> {
 impl<'a> Parser<'a> {
     pub fn new(main: &'a mut Sapphire, tokens: Vec<Token>) -> Self {
         Self {
-            main: main,
-            tokens: tokens,
-            current: 0 as usize
+            main,
+            tokens,
+            current: 0_usize
         }
     }
 
@@ -28,6 +32,10 @@ impl<'a> Parser<'a> {
     }
 
     pub fn synchronize(&mut self) {
+        if self.is_at_end() {
+            return;
+        }
+
         let _ = self.advance();
 
         while !self.is_at_end() {
@@ -37,9 +45,10 @@ impl<'a> Parser<'a> {
             
             let peeked_token: &Token = self.peek();
             match peeked_token.token_type {
-                TokenType::Class | TokenType::Fn | TokenType::Var | 
+                TokenType::Class | TokenType::Fn | TokenType::Var | TokenType::Const |
                     TokenType::For | TokenType::If | TokenType::While | 
-                    TokenType::PrintLn | TokenType::Return => return,
+                    TokenType::Print | TokenType::Return | TokenType::Throw | TokenType::Try |
+                    TokenType::Break | TokenType::Continue => return,
                 _ => (),
             }
 
@@ -55,6 +64,14 @@ impl<'a> Parser<'a> {
         &self.tokens[self.current - 1]
     }
 
+    pub fn check_next(&mut self, token_type: TokenType) -> bool {
+        self.check_ahead(1, token_type)
+    }
+
+    pub fn check_ahead(&mut self, distance: usize, token_type: TokenType) -> bool {
+        self.tokens.get(self.current + distance).is_some_and(|token| token.token_type == token_type)
+    }
+
     pub fn is_at_end(&mut self) -> bool {
         self.peek().token_type == TokenType::EOF
     }
@@ -65,7 +82,7 @@ impl<'a> Parser<'a> {
             return Ok(self.previous());
         }
 
-        self.main.error(0 as usize, "Expected expression".to_string());
+        self.main.error(0_usize, "Expected expression".to_string());
         Err(ParseError::new("Expected expression"))
     }
 
@@ -90,18 +107,460 @@ impl<'a> Parser<'a> {
     }
 
     pub fn consume(&mut self, token_type: TokenType, message: String) -> Result<&Token, ParseError> {
-        if self.check(token_type) { return Ok(self.advance()?); }
+        if self.check(token_type) { return self.advance(); }
 
         let next_token: Token = self.peek().clone();
         Err(self.error(next_token, message))
     }
 
-    pub fn parse(&mut self) -> Result<Expr, impl Error> {
-        self.expression()
+    pub fn parse(&mut self) -> Vec<Stmt> {
+        let mut statements: Vec<Stmt> = vec![];
+
+        while !self.is_at_end() {
+            if let Some(stmt) = self.declaration() {
+                statements.push(stmt);
+            }
+        }
+
+        statements
+    }
+
+    pub fn declaration(&mut self) -> Option<Stmt> {
+        let result: Result<Stmt, ParseError> = if self.match_types(vec![TokenType::Class]) {
+            self.class_declaration()
+        // `fn (` starts a lambda, which is left to the expression statement.
+        } else if self.check(TokenType::Fn) && !self.check_next(TokenType::LeftParen) {
+            let _ = self.advance();
+            self.function("function")
+        } else if self.match_types(vec![TokenType::Var]) {
+            self.var_declaration()
+        } else if self.match_types(vec![TokenType::Const]) {
+            self.const_declaration()
+        } else {
+            self.statement()
+        };
+
+        match result {
+            Ok(stmt) => Some(stmt),
+            Err(_) => {
+                self.synchronize();
+                None
+            }
+        }
+    }
+
+    pub fn class_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let name: Token = self.consume(TokenType::Identifier, String::from("Expect class name."))?.clone();
+
+        let mut superclass: Option<Expr> = None;
+        if self.match_types(vec![TokenType::Less]) {
+            let superclass_name: Token = self.consume(TokenType::Identifier, String::from("Expect superclass name."))?.clone();
+            superclass = Some(Expr::Variable { name: superclass_name, depth: Cell::new(None) });
+        }
+
+        self.consume(TokenType::LeftBrace, String::from("Expect '{' before class body."))?;
+
+        let mut methods: Vec<Stmt> = vec![];
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            methods.push(self.function("method")?);
+        }
+
+        self.consume(TokenType::RightBrace, String::from("Expect '}' after class body."))?;
+        Ok(Stmt::Class { name, superclass, methods })
+    }
+
+    pub fn function(&mut self, kind: &str) -> Result<Stmt, ParseError> {
+        let name: Token = self.consume(TokenType::Identifier, format!("Expect {kind} name."))?.clone();
+        self.consume(TokenType::LeftParen, format!("Expect '(' after {kind} name."))?;
+        let params: Vec<Token> = self.parameters(TokenType::RightParen, "')'")?;
+
+        self.consume(TokenType::LeftBrace, format!("Expect '{{' before {kind} body."))?;
+        let body: Vec<Stmt> = self.block()?;
+
+        Ok(Stmt::Function { name, params, body: Rc::new(body) })
+    }
+
+    // Parses a comma separated parameter list up to and including `closing`.
+    pub fn parameters(&mut self, closing: TokenType, closing_name: &str) -> Result<Vec<Token>, ParseError> {
+        let mut params: Vec<Token> = vec![];
+        if !self.check(closing.clone()) {
+            loop {
+                params.push(self.consume(TokenType::Identifier, String::from("Expect parameter name."))?.clone());
+
+                if !self.match_types(vec![TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+        self.consume(closing, format!("Expect {closing_name} after parameters."))?;
+
+        Ok(params)
+    }
+
+    pub fn var_declaration(&mut self) -> Result<Stmt, ParseError> {
+        // `var [a, b] = ...` and `var {host, port} = ...` unpack with the `match` patterns.
+        if self.check(TokenType::LeftBracket) || self.check(TokenType::LeftBrace) {
+            let keyword: Token = self.previous().clone();
+            let pattern: Pattern = self.single_pattern()?;
+
+            self.consume(TokenType::Equal, String::from("Expect '=' after destructuring pattern."))?;
+            let initializer: Expr = self.expression()?;
+
+            self.consume(TokenType::Semicolon, String::from("Expect ';' after variable declaration."))?;
+            return Ok(Stmt::Destructure { keyword, pattern, initializer });
+        }
+
+        let name: Token = self.consume(TokenType::Identifier, String::from("Expect variable name."))?.clone();
+
+        let mut initializer: Expr = Expr::Literal { value: Value::Null };
+        if self.match_types(vec![TokenType::Equal]) {
+            initializer = self.expression()?;
+        }
+
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after variable declaration."))?;
+        Ok(Stmt::Var { name, initializer })
+    }
+
+    pub fn const_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let name: Token = self.consume(TokenType::Identifier, String::from("Expect constant name."))?.clone();
+
+        self.consume(TokenType::Equal, String::from("Expect '=' after constant name."))?;
+        let initializer: Expr = self.expression()?;
+
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after constant declaration."))?;
+        Ok(Stmt::Const { name, initializer })
+    }
+
+    pub fn statement(&mut self) -> Result<Stmt, ParseError> {
+        // `name: while (...)` labels a loop for `break name;` and `continue name;`.
+        if self.check(TokenType::Identifier) && self.check_next(TokenType::Colon) {
+            let label: Token = self.advance()?.clone();
+            let _ = self.advance();
+
+            if self.match_types(vec![TokenType::For]) {
+                return self.for_statement(Some(label));
+            }
+
+            if self.match_types(vec![TokenType::While]) {
+                return self.while_statement(Some(label));
+            }
+
+            let next_token: Token = self.peek().clone();
+            return Err(self.error(next_token, String::from("Expect loop after label.")));
+        }
+
+        if self.match_types(vec![TokenType::For]) {
+            return self.for_statement(None);
+        }
+
+        if self.match_types(vec![TokenType::If]) {
+            return self.if_statement();
+        }
+
+        if self.match_types(vec![TokenType::While]) {
+            return self.while_statement(None);
+        }
+
+        if self.match_types(vec![TokenType::Break]) {
+            let keyword: Token = self.previous().clone();
+            let label: Option<Token> = self.loop_label("break")?;
+            return Ok(Stmt::Break { keyword, label });
+        }
+
+        if self.match_types(vec![TokenType::Continue]) {
+            let keyword: Token = self.previous().clone();
+            let label: Option<Token> = self.loop_label("continue")?;
+            return Ok(Stmt::Continue { keyword, label });
+        }
+
+        if self.match_types(vec![TokenType::Print]) {
+            return self.print_statement();
+        }
+
+        if self.match_types(vec![TokenType::Return]) {
+            return self.return_statement();
+        }
+
+        if self.match_types(vec![TokenType::Throw]) {
+            return self.throw_statement();
+        }
+
+        if self.match_types(vec![TokenType::Try]) {
+            return self.try_statement();
+        }
+
+        // `{"key": ...`, `{1: ...` or `{key: ...` can only be a map literal, so it is
+        // left to the expression statement; any other `{` opens a block. A block
+        // may still start with a labeled loop, `{outer: while ...`.
+        let labeled_loop: bool = self.check_ahead(3, TokenType::While) || self.check_ahead(3, TokenType::For);
+        let map_literal: bool = self.check(TokenType::LeftBrace)
+            && (self.check_next(TokenType::String) || self.check_next(TokenType::Number)
+                || (self.check_next(TokenType::Identifier) && !labeled_loop))
+            && self.check_ahead(2, TokenType::Colon);
+
+        if !map_literal && self.match_types(vec![TokenType::LeftBrace]) {
+            return Ok(Stmt::Block { statements: self.block()? });
+        }
+
+        self.expression_statement()
+    }
+
+    pub fn block(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let mut statements: Vec<Stmt> = vec![];
+
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            if let Some(stmt) = self.declaration() {
+                statements.push(stmt);
+            }
+        }
+
+        self.consume(TokenType::RightBrace, String::from("Expect '}' after block."))?;
+        Ok(statements)
+    }
+
+    // Parses the optional label and the `;` that end a `break` or `continue`.
+    pub fn loop_label(&mut self, kind: &str) -> Result<Option<Token>, ParseError> {
+        let mut label: Option<Token> = None;
+        if self.match_types(vec![TokenType::Identifier]) {
+            label = Some(self.previous().clone());
+        }
+
+        self.consume(TokenType::Semicolon, format!("Expect ';' after '{kind}'."))?;
+        Ok(label)
+    }
+
+    // Desugars `for (init; cond; step) body` into a while loop inside a block,
+    // with `step` kept as the loop's `increment` so `continue` still runs it.
+    pub fn for_statement(&mut self, label: Option<Token>) -> Result<Stmt, ParseError> {
+        self.consume(TokenType::LeftParen, String::from("Expect '(' after 'for'."))?;
+
+        if self.check(TokenType::Identifier) && (self.check_next(TokenType::In) || self.check_next(TokenType::Comma)) {
+            return self.for_in_statement(label);
+        }
+
+        let initializer: Option<Stmt> = if self.match_types(vec![TokenType::Semicolon]) {
+            None
+        } else if self.match_types(vec![TokenType::Var]) {
+            Some(self.var_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let mut condition: Expr = Expr::Literal { value: Value::Bool(true) };
+        if !self.check(TokenType::Semicolon) {
+            condition = self.expression()?;
+        }
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after loop condition."))?;
+
+        let mut increment: Option<Expr> = None;
+        if !self.check(TokenType::RightParen) {
+            increment = Some(self.expression()?);
+        }
+        self.consume(TokenType::RightParen, String::from("Expect ')' after for clauses."))?;
+
+        let body: Stmt = self.statement()?;
+        let mut body: Stmt = Stmt::While { label, condition, body: Box::new(body), increment };
+
+        if let Some(initializer) = initializer {
+            body = Stmt::Block { statements: vec![initializer, body] };
+        }
+
+        Ok(body)
+    }
+
+    // `for (item in iterable)`, or `for (key, value in iterable)` for the
+    // entries of a map or the indices and elements of a list.
+    pub fn for_in_statement(&mut self, label: Option<Token>) -> Result<Stmt, ParseError> {
+        let mut names: Vec<Token> = vec![self.advance()?.clone()];
+        if self.match_types(vec![TokenType::Comma]) {
+            names.push(self.consume(TokenType::Identifier, String::from("Expect second loop variable name."))?.clone());
+        }
+
+        let keyword: Token = self.consume(TokenType::In, String::from("Expect 'in' after loop variable."))?.clone();
+        let iterable: Expr = self.expression()?;
+        self.consume(TokenType::RightParen, String::from("Expect ')' after iterable."))?;
+
+        let body: Stmt = self.statement()?;
+        Ok(Stmt::ForIn { label, names, keyword, iterable, body: Box::new(body) })
+    }
+
+    pub fn if_statement(&mut self) -> Result<Stmt, ParseError> {
+        self.consume(TokenType::LeftParen, String::from("Expect '(' after 'if'."))?;
+        let condition: Expr = self.expression()?;
+        self.consume(TokenType::RightParen, String::from("Expect ')' after if condition."))?;
+
+        let then_branch: Stmt = self.statement()?;
+        let mut else_branch: Option<Box<Stmt>> = None;
+        if self.match_types(vec![TokenType::Else]) {
+            else_branch = Some(Box::new(self.statement()?));
+        }
+
+        Ok(Stmt::If { condition, then_branch: Box::new(then_branch), else_branch })
+    }
+
+    pub fn while_statement(&mut self, label: Option<Token>) -> Result<Stmt, ParseError> {
+        self.consume(TokenType::LeftParen, String::from("Expect '(' after 'while'."))?;
+        let condition: Expr = self.expression()?;
+        self.consume(TokenType::RightParen, String::from("Expect ')' after condition."))?;
+        let body: Stmt = self.statement()?;
+
+        Ok(Stmt::While { label, condition, body: Box::new(body), increment: None })
+    }
+
+    pub fn print_statement(&mut self) -> Result<Stmt, ParseError> {
+        let expression: Expr = self.expression()?;
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after value."))?;
+        Ok(Stmt::Print { expression })
+    }
+
+    pub fn throw_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword: Token = self.previous().clone();
+        let value: Expr = self.expression()?;
+
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after thrown value."))?;
+        Ok(Stmt::Throw { keyword, value })
+    }
+
+    pub fn try_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword: Token = self.previous().clone();
+        self.consume(TokenType::LeftBrace, String::from("Expect '{' after 'try'."))?;
+        let body: Vec<Stmt> = self.block()?;
+
+        let mut catch_name: Option<Token> = None;
+        let mut catch_body: Vec<Stmt> = vec![];
+        if self.match_types(vec![TokenType::Catch]) {
+            self.consume(TokenType::LeftParen, String::from("Expect '(' after 'catch'."))?;
+            catch_name = Some(self.consume(TokenType::Identifier, String::from("Expect error variable name."))?.clone());
+            self.consume(TokenType::RightParen, String::from("Expect ')' after error variable name."))?;
+
+            self.consume(TokenType::LeftBrace, String::from("Expect '{' before catch body."))?;
+            catch_body = self.block()?;
+        }
+
+        let mut finally_body: Vec<Stmt> = vec![];
+        if self.match_types(vec![TokenType::Finally]) {
+            self.consume(TokenType::LeftBrace, String::from("Expect '{' after 'finally'."))?;
+            finally_body = self.block()?;
+        } else if catch_name.is_none() {
+            return Err(self.error(keyword, String::from("Expect 'catch' or 'finally' after try block.")));
+        }
+
+        Ok(Stmt::Try { body, catch_name, catch_body, finally_body })
+    }
+
+    pub fn return_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword: Token = self.previous().clone();
+
+        let mut value: Option<Expr> = None;
+        if !self.check(TokenType::Semicolon) {
+            value = Some(self.expression()?);
+        }
+
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after return value."))?;
+        Ok(Stmt::Return { keyword, value })
+    }
+
+    pub fn expression_statement(&mut self) -> Result<Stmt, ParseError> {
+        let expression: Expr = self.expression()?;
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after expression."))?;
+        Ok(Stmt::Expression { expression })
     }
 
     pub fn expression(&mut self) -> Result<Expr, ParseError> {
-        self.equality()
+        self.assignment()
+    }
+
+    pub fn assignment(&mut self) -> Result<Expr, ParseError> {
+        let expr: Expr = self.conditional()?;
+
+        if self.match_types(vec![TokenType::Equal]) {
+            let equals: Token = self.previous().clone();
+            let value: Expr = self.assignment()?;
+
+            return match expr {
+                Expr::Variable { name, depth: _ } => Ok(Expr::Assign { name, value: Box::new(value), depth: Cell::new(None) }),
+                Expr::Get { object, name } => Ok(Expr::Set { object, name, value: Box::new(value) }),
+                Expr::Index { object, bracket, index } => Ok(Expr::IndexSet { object, bracket, index, value: Box::new(value) }),
+                _ => {
+                    // Report without unwinding, the parser is not in a confused state.
+                    self.error(equals, String::from("Invalid assignment target."));
+                    Ok(expr)
+                }
+            };
+        }
+
+        if self.match_types(vec![TokenType::PlusEqual, TokenType::MinusEqual, TokenType::StarEqual,
+            TokenType::SlashEqual, TokenType::DoubleDotEqual]) {
+            let operator: Token = self.previous().clone();
+            let value: Expr = self.assignment()?;
+
+            return match expr {
+                Expr::Variable { .. } | Expr::Get { .. } | Expr::Index { .. } => {
+                    Ok(Expr::Compound { target: Box::new(expr), operator, value: Box::new(value), postfix: false })
+                },
+                _ => {
+                    self.error(operator, String::from("Invalid assignment target."));
+                    Ok(expr)
+                }
+            };
+        }
+
+        Ok(expr)
+    }
+
+    pub fn conditional(&mut self) -> Result<Expr, ParseError> {
+        let condition: Expr = self.coalesce()?;
+
+        if self.match_types(vec![TokenType::Question]) {
+            let then_branch: Expr = self.expression()?;
+            self.consume(TokenType::Colon, String::from("Expect ':' after then branch of conditional expression."))?;
+            let else_branch: Expr = self.conditional()?;
+
+            return Ok(Expr::Conditional { condition: Box::new(condition), then_branch: Box::new(then_branch), else_branch: Box::new(else_branch) });
+        }
+
+        Ok(condition)
+    }
+
+    // `??` only falls back when the left side is nil, so it is a logical
+    // operator of its own rather than sugar for `or`.
+    pub fn coalesce(&mut self) -> Result<Expr, ParseError> {
+        let mut expr: Expr = self.or()?;
+
+        while self.match_types(vec![TokenType::QuestionQuestion]) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.or()?;
+
+            expr = Expr::Logical { left: Box::new(expr), operator, right: Box::new(right) };
+        }
+
+        Ok(expr)
+    }
+
+    pub fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr: Expr = self.and()?;
+
+        while self.match_types(vec![TokenType::Or]) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.and()?;
+
+            expr = Expr::Logical { left: Box::new(expr), operator, right: Box::new(right) };
+        }
+
+        Ok(expr)
+    }
+
+    pub fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr: Expr = self.equality()?;
+
+        while self.match_types(vec![TokenType::And]) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.equality()?;
+
+            expr = Expr::Logical { left: Box::new(expr), operator, right: Box::new(right) };
+        }
+
+        Ok(expr)
     }
 
     pub fn equality(&mut self) -> Result<Expr, ParseError> {
@@ -111,28 +570,96 @@ impl<'a> Parser<'a> {
             let operator: Token = self.previous().clone();
             let right: Expr = self.comparison()?;
             
-            expr = Expr::Binary { left: Box::new(expr), operator: operator, right: Box::new(right) };
+            expr = Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) };
         }
 
         Ok(expr)
     }
 
     pub fn comparison(&mut self) -> Result<Expr, ParseError> {
-        let mut expr: Expr = self.term()?;
+        let mut expr: Expr = self.bit_or()?;
 
         while self.match_types(vec![
                 TokenType::Greater, TokenType::GreaterEqual, TokenType::Less, TokenType::LessEqual
             ]) {
             
             let operator: Token = self.previous().clone();
-            let right: Expr = self.term()?;
+            let right: Expr = self.bit_or()?;
 
-            expr = Expr::Binary { left: Box::new(expr), operator: operator, right: Box::new(right) };
+            expr = Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) };
         }
     
         Ok(expr)
     }
 
+    // Bitwise operators bind tighter than comparisons, so `a & mask == 0`
+    // means `(a & mask) == 0`.
+    pub fn bit_or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr: Expr = self.bit_xor()?;
+
+        while self.match_types(vec![TokenType::Pipe]) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.bit_xor()?;
+
+            expr = Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) };
+        }
+
+        Ok(expr)
+    }
+
+    pub fn bit_xor(&mut self) -> Result<Expr, ParseError> {
+        let mut expr: Expr = self.bit_and()?;
+
+        while self.match_types(vec![TokenType::Caret]) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.bit_and()?;
+
+            expr = Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) };
+        }
+
+        Ok(expr)
+    }
+
+    pub fn bit_and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr: Expr = self.shift()?;
+
+        while self.match_types(vec![TokenType::Ampersand]) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.shift()?;
+
+            expr = Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) };
+        }
+
+        Ok(expr)
+    }
+
+    pub fn shift(&mut self) -> Result<Expr, ParseError> {
+        let mut expr: Expr = self.range()?;
+
+        while self.match_types(vec![TokenType::LessLess, TokenType::GreaterGreater]) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.range()?;
+
+            expr = Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) };
+        }
+
+        Ok(expr)
+    }
+
+    // `start..<end` is half-open; it binds looser than arithmetic so `0..<n + 1` works.
+    pub fn range(&mut self) -> Result<Expr, ParseError> {
+        let expr: Expr = self.term()?;
+
+        if self.match_types(vec![TokenType::DoubleDotLess]) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.term()?;
+
+            return Ok(Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) });
+        }
+
+        Ok(expr)
+    }
+
     pub fn term(&mut self) -> Result<Expr, ParseError> {
         let mut expr: Expr = self.factor()?;
 
@@ -149,7 +676,7 @@ impl<'a> Parser<'a> {
     pub fn factor(&mut self) -> Result<Expr, ParseError> {
         let mut expr: Expr = self.unary()?;
 
-        while self.match_types(vec![TokenType::Slash, TokenType::Star]) {
+        while self.match_types(vec![TokenType::Slash, TokenType::Star, TokenType::Percent]) {
             let operator: Token = self.previous().clone();
             let right: Expr = self.unary()?;
 
@@ -160,14 +687,92 @@ impl<'a> Parser<'a> {
     }
 
     pub fn unary(&mut self) -> Result<Expr, ParseError> {
-        if self.match_types(vec![TokenType::Bang, TokenType::Minus]) {
+        if self.match_types(vec![TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator: Token = self.previous().clone();
+            let target: Expr = self.unary()?;
+
+            return Ok(self.increment(target, operator, false));
+        }
+
+        if self.match_types(vec![TokenType::Bang, TokenType::Minus, TokenType::Tilde]) {
             let operator: Token = self.previous().clone();
             let right: Expr = self.unary()?;
 
             return Ok(Expr::Unary { operator, right: Box::new(right) });
         }
 
-        self.primary()
+        self.power()
+    }
+
+    // `**` is right-associative and binds tighter than a unary operator on
+    // its left, so `-2 ** 2` is `-(2 ** 2)`, while `2 ** -1` still parses.
+    pub fn power(&mut self) -> Result<Expr, ParseError> {
+        let expr: Expr = self.call()?;
+
+        if self.match_types(vec![TokenType::StarStar]) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.unary()?;
+
+            return Ok(Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) });
+        }
+
+        Ok(expr)
+    }
+
+    pub fn call(&mut self) -> Result<Expr, ParseError> {
+        let mut expr: Expr = self.primary()?;
+
+        loop {
+            if self.match_types(vec![TokenType::LeftParen]) {
+                expr = self.finish_call(expr)?;
+            } else if self.match_types(vec![TokenType::Dot]) {
+                let name: Token = self.consume(TokenType::Identifier, String::from("Expect property name after '.'."))?.clone();
+                expr = Expr::Get { object: Box::new(expr), name };
+            } else if self.match_types(vec![TokenType::LeftBracket]) {
+                let index: Expr = self.expression()?;
+                let bracket: Token = self.consume(TokenType::RightBracket, String::from("Expect ']' after index."))?.clone();
+                expr = Expr::Index { object: Box::new(expr), bracket, index: Box::new(index) };
+            } else {
+                break;
+            }
+        }
+
+        if self.match_types(vec![TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator: Token = self.previous().clone();
+            expr = self.increment(expr, operator, true);
+        }
+
+        Ok(expr)
+    }
+
+    // `++x` and `x++` are `x += 1`; the postfix form hands back the old value.
+    pub fn increment(&mut self, target: Expr, operator: Token, postfix: bool) -> Expr {
+        match target {
+            Expr::Variable { .. } | Expr::Get { .. } | Expr::Index { .. } => {
+                let value: Expr = Expr::Literal { value: Value::Int(1) };
+                Expr::Compound { target: Box::new(target), operator, value: Box::new(value), postfix }
+            },
+            _ => {
+                self.error(operator, String::from("Invalid increment target."));
+                target
+            }
+        }
+    }
+
+    pub fn finish_call(&mut self, callee: Expr) -> Result<Expr, ParseError> {
+        let mut arguments: Vec<Expr> = vec![];
+        if !self.check(TokenType::RightParen) {
+            loop {
+                arguments.push(self.expression()?);
+
+                if !self.match_types(vec![TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        let paren: Token = self.consume(TokenType::RightParen, String::from("Expect ')' after arguments."))?.clone();
+        Ok(Expr::Call { callee: Box::new(callee), paren, arguments })
     }
 
     pub fn primary(&mut self) -> Result<Expr, ParseError> {
@@ -180,6 +785,21 @@ impl<'a> Parser<'a> {
             TokenType::Number | TokenType::String => {
                 return Ok(Expr::Literal { value: next_token.literal })
             },
+            TokenType::Interpolation => return self.interpolation(next_token),
+            TokenType::Identifier => return Ok(Expr::Variable { name: next_token, depth: Cell::new(None) }),
+            TokenType::This => return Ok(Expr::This { keyword: next_token, depth: Cell::new(None) }),
+            TokenType::Super => {
+                self.consume(TokenType::Dot, String::from("Expect '.' after 'super'."))?;
+                let method: Token = self.consume(TokenType::Identifier, String::from("Expect superclass method name."))?.clone();
+                return Ok(Expr::Super { keyword: next_token, method, depth: Cell::new(None) });
+            },
+            TokenType::Match => return self.match_expression(next_token),
+            TokenType::Fn => return self.lambda(next_token),
+            TokenType::Pipe => return self.arrow_lambda(next_token),
+            TokenType::LeftBracket => return self.list(),
+            // A `{` that starts a statement was taken as a block unless it
+            // looked like a map, so here it can only open a map literal.
+            TokenType::LeftBrace => return self.map(next_token),
             TokenType::LeftParen => {
                 let expr: Expr = self.expression()?;
                 self.consume(TokenType::RightParen, String::from("Expect ')' to close grouping expression."))?;
@@ -190,4 +810,205 @@ impl<'a> Parser<'a> {
 
         Err(self.error(next_token, "Expected expression".to_string()))
     }
-} 
\ No newline at end of file
+
+    // The scanner splits `"a ${x} b"` into Interpolation("a "), the tokens of
+    // `x`, then String(" b"); segments can repeat before the closing String.
+    pub fn interpolation(&mut self, first: Token) -> Result<Expr, ParseError> {
+        let mut parts: Vec<Expr> = vec![Expr::Literal { value: first.literal }];
+
+        loop {
+            parts.push(self.expression()?);
+
+            if self.match_types(vec![TokenType::Interpolation]) {
+                parts.push(Expr::Literal { value: self.previous().literal.clone() });
+                continue;
+            }
+
+            let tail: Token = self.consume(TokenType::String, String::from("Expect '}' after interpolated expression."))?.clone();
+            parts.push(Expr::Literal { value: tail.literal });
+
+            return Ok(Expr::Interpolation { parts });
+        }
+    }
+
+    pub fn lambda(&mut self, keyword: Token) -> Result<Expr, ParseError> {
+        self.consume(TokenType::LeftParen, String::from("Expect '(' after 'fn'."))?;
+        let params: Vec<Token> = self.parameters(TokenType::RightParen, "')'")?;
+
+        self.consume(TokenType::LeftBrace, String::from("Expect '{' before lambda body."))?;
+        let body: Vec<Stmt> = self.block()?;
+
+        Ok(Expr::Lambda { keyword, params, body: Rc::new(body) })
+    }
+
+    // `|x| x * 2` is shorthand for `fn (x) { return x * 2; }`.
+    pub fn arrow_lambda(&mut self, keyword: Token) -> Result<Expr, ParseError> {
+        let params: Vec<Token> = self.parameters(TokenType::Pipe, "'|'")?;
+        let value: Expr = self.expression()?;
+
+        let body: Vec<Stmt> = vec![Stmt::Return { keyword: keyword.clone(), value: Some(value) }];
+        Ok(Expr::Lambda { keyword, params, body: Rc::new(body) })
+    }
+
+    pub fn list(&mut self) -> Result<Expr, ParseError> {
+        let mut elements: Vec<Expr> = vec![];
+
+        // A trailing comma is allowed so long literals can be one item per line.
+        while !self.check(TokenType::RightBracket) {
+            elements.push(self.expression()?);
+
+            if !self.match_types(vec![TokenType::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBracket, String::from("Expect ']' after list elements."))?;
+        Ok(Expr::List { elements })
+    }
+
+    pub fn map(&mut self, brace: Token) -> Result<Expr, ParseError> {
+        let mut keys: Vec<Expr> = vec![];
+        let mut values: Vec<Expr> = vec![];
+
+        while !self.check(TokenType::RightBrace) {
+            // A bare name is a string key, as in map patterns; `(name)` uses the variable.
+            if self.check(TokenType::Identifier) && self.check_next(TokenType::Colon) {
+                let name: Token = self.advance()?.clone();
+                keys.push(Expr::Literal { value: Value::Str(name.lexeme) });
+            } else {
+                keys.push(self.expression()?);
+            }
+            self.consume(TokenType::Colon, String::from("Expect ':' after map key."))?;
+            values.push(self.expression()?);
+
+            if !self.match_types(vec![TokenType::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBrace, String::from("Expect '}' after map entries."))?;
+        Ok(Expr::Map { brace, keys, values })
+    }
+
+    pub fn match_expression(&mut self, keyword: Token) -> Result<Expr, ParseError> {
+        let subject: Expr = self.expression()?;
+        self.consume(TokenType::LeftBrace, String::from("Expect '{' after match subject."))?;
+
+        let mut arms: Vec<MatchArm> = vec![];
+        while !self.check(TokenType::RightBrace) {
+            let pattern: Pattern = self.pattern()?;
+
+            let mut guard: Option<Expr> = None;
+            if self.match_types(vec![TokenType::If]) {
+                guard = Some(self.expression()?);
+            }
+
+            self.consume(TokenType::FatArrow, String::from("Expect '=>' after match pattern."))?;
+            let body: Expr = self.expression()?;
+            arms.push(MatchArm { pattern, guard, body });
+
+            if !self.match_types(vec![TokenType::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBrace, String::from("Expect '}' after match arms."))?;
+        Ok(Expr::Match { keyword, subject: Box::new(subject), arms })
+    }
+
+    pub fn pattern(&mut self) -> Result<Pattern, ParseError> {
+        let mut alternatives: Vec<Pattern> = vec![self.single_pattern()?];
+        while self.match_types(vec![TokenType::Pipe]) {
+            alternatives.push(self.single_pattern()?);
+        }
+
+        if alternatives.len() == 1 {
+            return Ok(alternatives.remove(0));
+        }
+
+        Ok(Pattern::Or(alternatives))
+    }
+
+    pub fn single_pattern(&mut self) -> Result<Pattern, ParseError> {
+        let next_token: Token = self.advance()?.clone();
+
+        match next_token.token_type {
+            TokenType::Identifier if next_token.lexeme == "_" => Ok(Pattern::Wildcard),
+            TokenType::Identifier => Ok(Pattern::Binding(next_token)),
+            TokenType::Number | TokenType::String => Ok(Pattern::Literal(next_token.literal)),
+            TokenType::True => Ok(Pattern::Literal(Value::Bool(true))),
+            TokenType::False => Ok(Pattern::Literal(Value::Bool(false))),
+            TokenType::Nil => Ok(Pattern::Literal(Value::Null)),
+            TokenType::Minus => {
+                let number: Token = self.consume(TokenType::Number, String::from("Expect number after '-' in pattern."))?.clone();
+                match number.literal {
+                    Value::Int(int) => Ok(Pattern::Literal(Value::Int(-int))),
+                    Value::Number(num) => Ok(Pattern::Literal(Value::Number(-num))),
+                    _ => unreachable!()
+                }
+            },
+            TokenType::LeftBracket => self.list_pattern(),
+            TokenType::LeftBrace => self.map_pattern(),
+            _ => Err(self.error(next_token, String::from("Expect pattern.")))
+        }
+    }
+
+    pub fn list_pattern(&mut self) -> Result<Pattern, ParseError> {
+        let mut elements: Vec<Pattern> = vec![];
+        let mut rest: Option<Box<Pattern>> = None;
+
+        while !self.check(TokenType::RightBracket) {
+            // `..rest` collects whatever is left and has to come last; a bare `..` ignores it.
+            if self.match_types(vec![TokenType::DoubleDot]) {
+                rest = Some(Box::new(if self.check(TokenType::Identifier) { self.single_pattern()? } else { Pattern::Wildcard }));
+                break;
+            }
+
+            elements.push(self.pattern()?);
+
+            if !self.match_types(vec![TokenType::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBracket, String::from("Expect ']' after list pattern."))?;
+        Ok(Pattern::List { elements, rest })
+    }
+
+    pub fn map_pattern(&mut self) -> Result<Pattern, ParseError> {
+        let mut entries: Vec<(MapKey, Pattern)> = vec![];
+
+        while !self.check(TokenType::RightBrace) {
+            let entry: (MapKey, Pattern) = if self.check(TokenType::Identifier) {
+                // A bare name is a string key; `{name}` is short for `{name: name}`.
+                let name: Token = self.advance()?.clone();
+                if self.match_types(vec![TokenType::Colon]) {
+                    (MapKey::Str(name.lexeme), self.pattern()?)
+                } else {
+                    (MapKey::Str(name.lexeme.clone()), Pattern::Binding(name))
+                }
+            } else {
+                // Otherwise any literal a map key can hold: a string, number or bool.
+                let token: Token = self.peek().clone();
+                let key: Option<MapKey> = match self.single_pattern()? {
+                    Pattern::Literal(value) => MapKey::from_value(&value),
+                    _ => None
+                };
+                let Some(key) = key else {
+                    return Err(self.error(token, String::from("Expect name or literal map key in map pattern.")));
+                };
+
+                self.consume(TokenType::Colon, String::from("Expect ':' after map pattern key."))?;
+                (key, self.pattern()?)
+            };
+            entries.push(entry);
+
+            if !self.match_types(vec![TokenType::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(TokenType::RightBrace, String::from("Expect '}' after map pattern."))?;
+        Ok(Pattern::Map { entries })
+    }
+}
\ No newline at end of file
@@ -0,0 +1,87 @@
+use std::fmt;
+
+use crate::expr::Expr;
+use crate::map::MapKey;
+use crate::token::Token;
+use crate::value::Value;
+
+// The shape a value is tested against by `match`, binding names as it goes.
+#[derive(Clone)]
+pub enum Pattern {
+    Literal(Value),
+    Wildcard,
+    Binding(Token),
+    // `[first, ..rest]`; the rest pattern receives the remaining elements as a List.
+    List { elements: Vec<Pattern>, rest: Option<Box<Pattern>> },
+    // `{name: n, 1: x}`; only the listed keys have to be present.
+    Map { entries: Vec<(MapKey, Pattern)> },
+    Or(Vec<Pattern>),
+}
+
+impl Pattern {
+    // Every name the pattern binds, in source order. Alternatives must all
+    // bind the same names, so only the first one is looked at.
+    pub fn bindings(&self) -> Vec<&Token> {
+        match self {
+            Pattern::Literal(_) | Pattern::Wildcard => vec![],
+            Pattern::Binding(name) => vec![name],
+            Pattern::List { elements, rest } => {
+                let mut names: Vec<&Token> = elements.iter().flat_map(|element| element.bindings()).collect();
+                if let Some(rest) = rest {
+                    names.extend(rest.bindings());
+                }
+                names
+            },
+            Pattern::Map { entries } => entries.iter().flat_map(|(_, pattern)| pattern.bindings()).collect(),
+            Pattern::Or(alternatives) => alternatives[0].bindings(),
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pattern::Literal(Value::Str(str)) => write!(f, "{:?}", str),
+            Pattern::Literal(value) => write!(f, "{}", value),
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Binding(name) => write!(f, "{}", name.lexeme),
+            Pattern::List { elements, rest } => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 { write!(f, ", ")?; }
+                    write!(f, "{}", element)?;
+                }
+                if let Some(rest) = rest {
+                    if !elements.is_empty() { write!(f, ", ")?; }
+                    write!(f, "..{}", rest)?;
+                }
+                write!(f, "]")
+            },
+            Pattern::Map { entries } => {
+                write!(f, "{{")?;
+                for (i, (key, pattern)) in entries.iter().enumerate() {
+                    if i > 0 { write!(f, ", ")?; }
+                    match key {
+                        MapKey::Str(name) => write!(f, "{}: {}", name, pattern)?,
+                        _ => write!(f, "{}: {}", key.to_value(), pattern)?,
+                    }
+                }
+                write!(f, "}}")
+            },
+            Pattern::Or(alternatives) => {
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    if i > 0 { write!(f, " | ")?; }
+                    write!(f, "{}", alternative)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Expr,
+}
\ No newline at end of file
@@ -0,0 +1,746 @@
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+
+use crate::expr::{self, Expr};
+use crate::pattern::Pattern;
+use crate::stmt::{self, Stmt};
+use crate::token::Token;
+use super::Sapphire;
+
+#[derive(Clone, Copy, PartialEq)]
+enum FunctionType {
+    None,
+    Function,
+    Initializer,
+    Method,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
+// Walks the tree once before execution, recording how many scopes separate
+// each variable use from its declaration.
+pub struct Resolver<'a> {
+    main: &'a mut Sapphire,
+    // Each scope maps a name to whether its initializer has finished.
+    scopes: Vec<HashMap<String, bool>>,
+    // The `const` names declared in each scope, kept in step with `scopes`.
+    constants: Vec<HashSet<String>>,
+    // Top-level `const` names from this run; earlier runs are read from the globals.
+    global_constants: HashSet<String>,
+    current_function: FunctionType,
+    current_class: ClassType,
+    // Labels of the loops enclosing the current statement, innermost last.
+    loops: Vec<Option<String>>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(main: &'a mut Sapphire) -> Self {
+        Self {
+            main,
+            scopes: vec![],
+            constants: vec![],
+            global_constants: HashSet::new(),
+            current_function: FunctionType::None,
+            current_class: ClassType::None,
+            loops: vec![],
+        }
+    }
+
+    pub fn resolve(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            self.resolve_stmt(statement);
+        }
+    }
+
+    fn resolve_stmt(&mut self, statement: &Stmt) {
+        statement.accept(self)
+    }
+
+    fn resolve_expr(&mut self, expression: &Expr) {
+        expression.accept(self)
+    }
+
+    fn resolve_function(&mut self, params: &[Token], body: &[Stmt], function_type: FunctionType) {
+        let enclosing_function: FunctionType = self.current_function;
+        self.current_function = function_type;
+        // A function body starts outside of any loop, even when declared inside one.
+        let enclosing_loops: Vec<Option<String>> = std::mem::take(&mut self.loops);
+
+        self.begin_scope();
+        for param in params {
+            self.declare(param);
+            self.define(param);
+        }
+        self.resolve(body);
+        self.end_scope();
+
+        self.loops = enclosing_loops;
+        self.current_function = enclosing_function;
+    }
+
+    fn resolve_jump(&mut self, keyword: &Token, label: &Option<Token>) {
+        if self.loops.is_empty() {
+            self.main.token_error(keyword.clone(), format!("Can't use '{}' outside of a loop.", keyword.lexeme));
+            return;
+        }
+
+        if let Some(label) = label
+            && !self.loops.iter().any(|loop_label| loop_label.as_ref() == Some(&label.lexeme)) {
+            self.main.token_error(label.clone(), format!("No enclosing loop labeled '{}'.", label.lexeme));
+        }
+    }
+
+    // Declares every name a pattern binds in the current scope.
+    fn declare_pattern(&mut self, pattern: &Pattern) {
+        self.check_alternatives(pattern);
+
+        for name in pattern.bindings() {
+            self.declare(name);
+            self.define(name);
+        }
+    }
+
+    // Each side of a `|` has to bind the same names, or some would be left undefined.
+    fn check_alternatives(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Literal(_) | Pattern::Wildcard | Pattern::Binding(_) => (),
+            Pattern::List { elements, rest } => {
+                for element in elements {
+                    self.check_alternatives(element);
+                }
+                if let Some(rest) = rest {
+                    self.check_alternatives(rest);
+                }
+            },
+            Pattern::Map { entries } => {
+                for (_, entry) in entries {
+                    self.check_alternatives(entry);
+                }
+            },
+            Pattern::Or(alternatives) => {
+                let mut expected: Vec<&str> = alternatives[0].bindings().iter().map(|name| name.lexeme.as_str()).collect();
+                expected.sort();
+
+                for alternative in alternatives {
+                    self.check_alternatives(alternative);
+
+                    let bindings: Vec<&Token> = alternative.bindings();
+                    let mut names: Vec<&str> = bindings.iter().map(|name| name.lexeme.as_str()).collect();
+                    names.sort();
+
+                    if names != expected {
+                        let name: Token = bindings.first().copied().cloned()
+                            .unwrap_or_else(|| alternatives[0].bindings()[0].clone());
+                        self.main.token_error(name, String::from("Alternatives in a pattern must bind the same names."));
+                    }
+                }
+            },
+        }
+    }
+
+    fn resolve_local(&mut self, name: &Token, depth: &Cell<Option<usize>>) {
+        for (distance, scope) in self.scopes.iter().rev().enumerate() {
+            if scope.contains_key(&name.lexeme) {
+                depth.set(Some(distance));
+                return;
+            }
+        }
+
+        // Not found in any local scope, so it is left to the globals.
+        depth.set(None);
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+        self.constants.push(HashSet::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+        self.constants.pop();
+    }
+
+    fn is_global_constant(&self, name: &str) -> bool {
+        self.global_constants.contains(name) || self.main.globals.borrow().is_constant(name)
+    }
+
+    // Rejects assigning to a `const`, given the depth the name resolved to.
+    fn check_assignable(&mut self, name: &Token, depth: Option<usize>) {
+        let constant: bool = match depth {
+            Some(distance) => self.constants[self.constants.len() - 1 - distance].contains(&name.lexeme),
+            None => self.is_global_constant(&name.lexeme)
+        };
+
+        if constant {
+            self.main.token_error(name.clone(), format!("Can't assign to constant '{}'.", name.lexeme));
+        }
+    }
+
+    fn declare(&mut self, name: &Token) {
+        let Some(scope) = self.scopes.last_mut() else {
+            // Globals may be redeclared, but not over a constant.
+            if self.is_global_constant(&name.lexeme) {
+                self.main.token_error(name.clone(), format!("Can't redefine constant '{}'.", name.lexeme));
+            }
+            return;
+        };
+
+        if scope.contains_key(&name.lexeme) {
+            self.main.token_error(name.clone(), String::from("Already a variable with this name in this scope."));
+            return;
+        }
+
+        scope.insert(name.lexeme.clone(), false);
+    }
+
+    fn define(&mut self, name: &Token) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), true);
+        }
+    }
+}
+
+impl stmt::Visitor for Resolver<'_> {
+    type Result = ();
+
+    fn visit_block(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Block { statements } = stmt {
+            self.begin_scope();
+            self.resolve(statements);
+            self.end_scope();
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_break(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Break { keyword, label } = stmt {
+            self.resolve_jump(keyword, label);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_const(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Const { name, initializer } = stmt {
+            self.declare(name);
+            self.resolve_expr(initializer);
+            self.define(name);
+
+            match self.constants.last_mut() {
+                Some(constants) => constants.insert(name.lexeme.clone()),
+                None => self.global_constants.insert(name.lexeme.clone()),
+            };
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_continue(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Continue { keyword, label } = stmt {
+            self.resolve_jump(keyword, label);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_class(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Class { name, superclass, methods } = stmt {
+            let enclosing_class: ClassType = self.current_class;
+            self.current_class = ClassType::Class;
+
+            self.declare(name);
+            self.define(name);
+
+            if let Some(superclass) = superclass {
+                if let Expr::Variable { name: superclass_name, depth: _ } = superclass
+                    && superclass_name.lexeme == name.lexeme {
+                    self.main.token_error(superclass_name.clone(), String::from("A class can't inherit from itself."));
+                }
+
+                self.current_class = ClassType::Subclass;
+                self.resolve_expr(superclass);
+
+                self.begin_scope();
+                self.scopes.last_mut().unwrap().insert(String::from("super"), true);
+            }
+
+            self.begin_scope();
+            self.scopes.last_mut().unwrap().insert(String::from("this"), true);
+
+            for method in methods {
+                if let Stmt::Function { name, params, body } = method {
+                    let function_type: FunctionType = if name.lexeme == "init" {
+                        FunctionType::Initializer
+                    } else {
+                        FunctionType::Method
+                    };
+
+                    self.resolve_function(params, body, function_type);
+                }
+            }
+
+            self.end_scope();
+
+            if superclass.is_some() {
+                self.end_scope();
+            }
+
+            self.current_class = enclosing_class;
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_destructure(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Destructure { keyword: _, pattern, initializer } = stmt {
+            self.check_alternatives(pattern);
+
+            let names: Vec<&Token> = pattern.bindings();
+            for name in &names {
+                self.declare(name);
+            }
+            self.resolve_expr(initializer);
+            for name in &names {
+                self.define(name);
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_expression(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Expression { expression } = stmt {
+            self.resolve_expr(expression);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_forin(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::ForIn { label, names, keyword: _, iterable, body } = stmt {
+            self.resolve_expr(iterable);
+
+            self.begin_scope();
+            for name in names {
+                self.declare(name);
+                self.define(name);
+            }
+
+            self.loops.push(label.as_ref().map(|label| label.lexeme.clone()));
+            self.resolve_stmt(body);
+            self.loops.pop();
+
+            self.end_scope();
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_function(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Function { name, params, body } = stmt {
+            // Defined eagerly so the function can refer to itself recursively.
+            self.declare(name);
+            self.define(name);
+
+            self.resolve_function(params, body, FunctionType::Function);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_if(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::If { condition, then_branch, else_branch } = stmt {
+            self.resolve_expr(condition);
+            self.resolve_stmt(then_branch);
+            if let Some(else_branch) = else_branch {
+                self.resolve_stmt(else_branch);
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_print(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Print { expression } = stmt {
+            self.resolve_expr(expression);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_return(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Return { keyword, value } = stmt {
+            if self.current_function == FunctionType::None {
+                self.main.token_error(keyword.clone(), String::from("Can't return from top-level code."));
+            }
+
+            if let Some(value) = value {
+                if self.current_function == FunctionType::Initializer {
+                    self.main.token_error(keyword.clone(), String::from("Can't return a value from an initializer."));
+                }
+
+                self.resolve_expr(value);
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_throw(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Throw { keyword: _, value } = stmt {
+            self.resolve_expr(value);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_try(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Try { body, catch_name, catch_body, finally_body } = stmt {
+            self.begin_scope();
+            self.resolve(body);
+            self.end_scope();
+
+            if let Some(catch_name) = catch_name {
+                self.begin_scope();
+                self.declare(catch_name);
+                self.define(catch_name);
+                self.resolve(catch_body);
+                self.end_scope();
+            }
+
+            self.begin_scope();
+            self.resolve(finally_body);
+            self.end_scope();
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_var(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::Var { name, initializer } = stmt {
+            self.declare(name);
+            self.resolve_expr(initializer);
+            self.define(name);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_while(&mut self, stmt: &Stmt) -> Self::Result {
+        if let Stmt::While { label, condition, body, increment } = stmt {
+            self.resolve_expr(condition);
+
+            self.loops.push(label.as_ref().map(|label| label.lexeme.clone()));
+            self.resolve_stmt(body);
+            self.loops.pop();
+
+            if let Some(increment) = increment {
+                self.resolve_expr(increment);
+            }
+        } else {
+            unreachable!()
+        }
+    }
+}
+
+impl expr::Visitor for Resolver<'_> {
+    type Result = ();
+
+    fn visit_assign(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Assign { name, value, depth } = expr {
+            self.resolve_expr(value);
+            self.resolve_local(name, depth);
+            self.check_assignable(name, depth.get());
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_binary(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Binary { left, operator: _, right } = expr {
+            self.resolve_expr(left);
+            self.resolve_expr(right);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_call(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Call { callee, paren: _, arguments } = expr {
+            self.resolve_expr(callee);
+            for argument in arguments {
+                self.resolve_expr(argument);
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_compound(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Compound { target, operator: _, value, postfix: _ } = expr {
+            self.resolve_expr(target);
+            self.resolve_expr(value);
+
+            if let Expr::Variable { name, depth } = target.as_ref() {
+                self.check_assignable(name, depth.get());
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_conditional(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Conditional { condition, then_branch, else_branch } = expr {
+            self.resolve_expr(condition);
+            self.resolve_expr(then_branch);
+            self.resolve_expr(else_branch);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_get(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Get { object, name: _ } = expr {
+            self.resolve_expr(object);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_grouping(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Grouping { expression } = expr {
+            self.resolve_expr(expression);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_index(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Index { object, bracket: _, index } = expr {
+            self.resolve_expr(object);
+            self.resolve_expr(index);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_indexset(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::IndexSet { object, bracket: _, index, value } = expr {
+            self.resolve_expr(object);
+            self.resolve_expr(index);
+            self.resolve_expr(value);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_interpolation(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Interpolation { parts } = expr {
+            for part in parts {
+                self.resolve_expr(part);
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_lambda(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Lambda { keyword: _, params, body } = expr {
+            self.resolve_function(params, body, FunctionType::Function);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_list(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::List { elements } = expr {
+            for element in elements {
+                self.resolve_expr(element);
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_literal(&mut self, _expr: &Expr) -> Self::Result {}
+
+    fn visit_map(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Map { brace: _, keys, values } = expr {
+            for (key, value) in keys.iter().zip(values) {
+                self.resolve_expr(key);
+                self.resolve_expr(value);
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_match(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Match { keyword: _, subject, arms } = expr {
+            self.resolve_expr(subject);
+
+            for arm in arms {
+                self.begin_scope();
+                self.declare_pattern(&arm.pattern);
+                if let Some(guard) = &arm.guard {
+                    self.resolve_expr(guard);
+                }
+                self.resolve_expr(&arm.body);
+                self.end_scope();
+            }
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_logical(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Logical { left, operator: _, right } = expr {
+            self.resolve_expr(left);
+            self.resolve_expr(right);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_set(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Set { object, name: _, value } = expr {
+            self.resolve_expr(value);
+            self.resolve_expr(object);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_super(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Super { keyword, method: _, depth } = expr {
+            match self.current_class {
+                ClassType::None => self.main.token_error(keyword.clone(), String::from("Can't use 'super' outside of a class.")),
+                ClassType::Class => self.main.token_error(keyword.clone(), String::from("Can't use 'super' in a class with no superclass.")),
+                ClassType::Subclass => ()
+            }
+
+            self.resolve_local(keyword, depth);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_this(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::This { keyword, depth } = expr {
+            if self.current_class == ClassType::None {
+                self.main.token_error(keyword.clone(), String::from("Can't use 'this' outside of a class."));
+                return;
+            }
+
+            self.resolve_local(keyword, depth);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_unary(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Unary { operator: _, right } = expr {
+            self.resolve_expr(right);
+        } else {
+            unreachable!()
+        }
+    }
+
+    fn visit_variable(&mut self, expr: &Expr) -> Self::Result {
+        if let Expr::Variable { name, depth } = expr {
+            let in_own_initializer: bool = self.scopes.last()
+                .is_some_and(|scope| scope.get(&name.lexeme) == Some(&false));
+
+            if in_own_initializer {
+                self.main.token_error(name.clone(), String::from("Can't read local variable in its own initializer."));
+            }
+
+            self.resolve_local(name, depth);
+        } else {
+            unreachable!()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sapphire;
+    use crate::parser::Parser;
+    use crate::scanner::Scanner;
+    use crate::stmt::Stmt;
+    use crate::token::Token;
+
+    use super::Resolver;
+
+    // Scans, parses and resolves `source` without running it, reporting
+    // whether any static error was found.
+    fn has_static_error(source: &str) -> bool {
+        let mut sapphire: Sapphire = Sapphire::new();
+        let tokens: Vec<Token> = Scanner::new(&mut sapphire, source.to_string()).scan_tokens();
+        let statements: Vec<Stmt> = Parser::new(&mut sapphire, tokens).parse();
+        assert!(!sapphire.had_error, "{source:?} failed to parse");
+
+        Resolver::new(&mut sapphire).resolve(&statements);
+        sapphire.had_error
+    }
+
+    #[test]
+    fn reports_scope_errors() {
+        assert!(has_static_error("{ var a = 1; { var a = a; } }"));
+        assert!(has_static_error("fn f() { var a = 1; var a = 2; }"));
+        assert!(has_static_error("return 1;"));
+        assert!(has_static_error("class A { init() { return 1; } }"));
+        assert!(has_static_error("class A < A {}"));
+        assert!(has_static_error("print this;"));
+        assert!(has_static_error("fn f() { super.g(); }"));
+    }
+
+    #[test]
+    fn accepts_valid_scopes() {
+        assert!(!has_static_error("var a = 1; var a = a;"));
+        assert!(!has_static_error("fn f(n) { if (n == 0) return 0; return f(n - 1); }"));
+        assert!(!has_static_error("class A { init() { return; } }"));
+    }
+
+    #[test]
+    fn reports_jumps_outside_loops() {
+        assert!(has_static_error("break;"));
+        assert!(has_static_error("continue;"));
+        assert!(has_static_error("while (true) { fn f() { break; } }"));
+        assert!(has_static_error("while (true) { var g = fn () { continue; }; }"));
+        assert!(has_static_error("outer: while (true) { break inner; }"));
+        assert!(has_static_error("outer: while (true) {} while (true) { continue outer; }"));
+    }
+
+    #[test]
+    fn accepts_jumps_inside_loops() {
+        assert!(!has_static_error("while (true) { break; }"));
+        assert!(!has_static_error("for (var i = 0; i < 3; i = i + 1) { if (i == 1) continue; }"));
+        assert!(!has_static_error("for (x in [1, 2]) { break; }"));
+        assert!(!has_static_error("outer: for (var i = 0; i < 3; i = i + 1) { while (true) { continue outer; } }"));
+        assert!(!has_static_error("outer: while (true) { inner: while (true) { break outer; } }"));
+    }
+
+    #[test]
+    fn reports_const_reassignment() {
+        assert!(has_static_error("const a = 1; a = 2;"));
+        assert!(has_static_error("const a = 1; a += 2;"));
+        assert!(has_static_error("const a = 1; a++;"));
+        assert!(has_static_error("{ const a = 1; fn f() { a = 2; } }"));
+        assert!(has_static_error("const a = 1; fn f() { --a; }"));
+        assert!(has_static_error("const a = 1; var a = 2;"));
+    }
+
+    #[test]
+    fn accepts_shadowed_consts() {
+        assert!(!has_static_error("const a = 1; { var a = 2; a = 3; }"));
+        assert!(!has_static_error("{ const a = 1; } var a = 2; a = 3;"));
+        assert!(!has_static_error("const a = 1; fn f(a) { a = 2; }"));
+    }
+}
\ No newline at end of file
@@ -8,8 +8,11 @@ use std::collections::HashMap;
 
 pub struct Scanner<'a> {
     pub main: &'a mut Sapphire,
-    pub source: String,
+    // Held as chars so positions can index it directly.
+    pub source: Vec<char>,
     tokens: Vec<Token>,
+    // Unclosed `{` count inside each `${ ... }` we are currently lexing.
+    interpolations: Vec<usize>,
     
     start: usize,
     current: usize,
@@ -19,19 +22,28 @@ pub struct Scanner<'a> {
 pub fn get_keywords() -> HashMap<String, TokenType> {
     HashMap::from([
         ("and".to_string(), TokenType::And),
+        ("break".to_string(), TokenType::Break),
+        ("catch".to_string(), TokenType::Catch),
         ("class".to_string(), TokenType::Class),
+        ("const".to_string(), TokenType::Const),
+        ("continue".to_string(), TokenType::Continue),
         ("else".to_string(), TokenType::Else),
         ("false".to_string(), TokenType::False),
+        ("finally".to_string(), TokenType::Finally),
         ("fn".to_string(), TokenType::Fn),
         ("for".to_string(), TokenType::For),
         ("if".to_string(), TokenType::If),
+        ("in".to_string(), TokenType::In),
+        ("match".to_string(), TokenType::Match),
         ("nil".to_string(), TokenType::Nil),
         ("or".to_string(), TokenType::Or),
         ("print".to_string(), TokenType::Print),
         ("return".to_string(), TokenType::Return),
         ("super".to_string(), TokenType::Super),
         ("this".to_string(), TokenType::This),
+        ("throw".to_string(), TokenType::Throw),
         ("true".to_string(), TokenType::True),
+        ("try".to_string(), TokenType::Try),
         ("var".to_string(), TokenType::Var),
         ("while".to_string(), TokenType::While),  
     ])
@@ -40,9 +52,10 @@ pub fn get_keywords() -> HashMap<String, TokenType> {
 impl<'a> Scanner<'a> {
     pub fn new(main: &'a mut Sapphire, source: String) -> Self {
         Self {
-            main: main,
-            source: source,
+            main,
+            source: source.chars().collect(),
             tokens: vec![],
+            interpolations: vec![],
             start: 0,
             current: 0,
             line: 1
@@ -50,54 +63,44 @@ impl<'a> Scanner<'a> {
     }
 
     fn is_at_end(&self) -> bool {
-        let source_length: usize = self.source.len();
-        let result: bool = self.current >= source_length;
-
-        result
+        self.current >= self.source.len()
     }
 
     fn next_at_end(&self) -> bool {
-        let source_length: usize = self.source.len();
-        let result: bool = (self.current + 1) >= source_length;
-
-        result
+        (self.current + 1) >= self.source.len()
     }
 
     fn advance(&mut self) -> char {
-        let char: Option<char> = self.source.chars()
-            .nth(self.current);
-        
+        let char: Option<char> = self.source.get(self.current).copied();
+
         self.current += 1;
 
-        match char {
-            Some(c) => return c,
-            None => return '\0',
-        }
+        char.unwrap_or('\0')
     }
 
     fn add_token_short(&mut self, token_type: TokenType) {
         self.add_token(token_type, Value::Null)
     }
 
+    // The text of the token currently being scanned.
+    fn lexeme(&self) -> String {
+        self.source[self.start..self.current].iter().collect()
+    }
+
     fn add_token(&mut self, token_type: TokenType, literal: Value) {
-        let lex: String = self.source.chars()
-            .skip(self.start)
-            .take(self.current - self.start)
-            .collect();
+        let lex: String = self.lexeme();
 
         self.tokens.push(Token {
-            token_type: token_type,
+            token_type,
             lexeme: lex,
-            literal: literal,
+            literal,
             line: self.line
         });
     }
 
     fn match_char(&mut self, expected_char: char) -> bool {
         if self.is_at_end() { return false; }
-        let next_char = self.source.chars()
-            .nth(self.current)
-            .unwrap();
+        let next_char = self.source[self.current];
 
         if next_char != expected_char { return false; }
         
@@ -115,9 +118,9 @@ impl<'a> Scanner<'a> {
     fn peek(&mut self) -> Result<char, ScanError>  {
         if self.is_at_end() { return Ok('\0'); }
 
-        match self.source.chars().nth(self.current) {
-            Some(c) => return Ok(c),
-            None => return Err(ScanError::new("Expected character, recieved None"))
+        match self.source.get(self.current) {
+            Some(c) => Ok(*c),
+            None => Err(ScanError::new("Expected character, recieved None"))
         }
     }
 
@@ -126,20 +129,17 @@ impl<'a> Scanner<'a> {
             return '\0';
         }
 
-        self.source
-            .chars()
-            .nth(self.current + 1)
-            .unwrap()
+        self.source[self.current + 1]
     }
 
     fn is_alpha(&mut self, character: char) -> bool {
-        (character >= 'a' && character <= 'z') ||
-        (character >= 'A' && character <= 'Z') ||
+        character.is_ascii_lowercase() ||
+        character.is_ascii_uppercase() ||
             character == '_'
     }
 
     fn is_digit(&mut self, character: char) -> bool {
-        character >= '0' && character <= '9'
+        character.is_ascii_digit()
     }
     
     fn is_alpha_numeric(&mut self, character: char) -> bool {
@@ -152,31 +152,99 @@ impl<'a> Scanner<'a> {
         match c {
             '(' => self.add_token_short(TokenType::LeftParen),
             ')' => self.add_token_short(TokenType::RightParen),
-            '{' => self.add_token_short(TokenType::LeftBrace),
-            '}' => self.add_token_short(TokenType::RightBrace),
+            '{' => {
+                if let Some(depth) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+
+                self.add_token_short(TokenType::LeftBrace);
+            },
+            '}' => {
+                match self.interpolations.last_mut() {
+                    // Closes a `${`, so pick the enclosing string back up.
+                    Some(0) => {
+                        self.interpolations.pop();
+                        self.string_body()?;
+                    },
+                    Some(depth) => {
+                        *depth -= 1;
+                        self.add_token_short(TokenType::RightBrace);
+                    },
+                    None => self.add_token_short(TokenType::RightBrace)
+                }
+            },
+            '[' => self.add_token_short(TokenType::LeftBracket),
+            ']' => self.add_token_short(TokenType::RightBracket),
+            ':' => self.add_token_short(TokenType::Colon),
             ',' => self.add_token_short(TokenType::Comma),
             '.' => {
                 if self.match_char('.') {
-                    self.add_token_short(TokenType::DoubleDot);
+                    if self.match_char('<') {
+                        self.add_token_short(TokenType::DoubleDotLess);
+                    } else {
+                        self.match_to_type('=', TokenType::DoubleDot, TokenType::DoubleDotEqual);
+                    }
                 } else {
                     self.add_token_short(TokenType::Dot);
                 }
             },
-            '-' => self.add_token_short(TokenType::Minus),
-            '+' => self.add_token_short(TokenType::Plus),
+            '-' => {
+                if self.match_char('-') {
+                    self.add_token_short(TokenType::MinusMinus);
+                } else {
+                    self.match_to_type('=', TokenType::Minus, TokenType::MinusEqual);
+                }
+            },
+            '+' => {
+                if self.match_char('+') {
+                    self.add_token_short(TokenType::PlusPlus);
+                } else {
+                    self.match_to_type('=', TokenType::Plus, TokenType::PlusEqual);
+                }
+            },
             ';' => self.add_token_short(TokenType::Semicolon),
-            '*' => self.add_token_short(TokenType::Star),
+            '*' => {
+                if self.match_char('*') {
+                    self.add_token_short(TokenType::StarStar);
+                } else {
+                    self.match_to_type('=', TokenType::Star, TokenType::StarEqual);
+                }
+            },
+            '&' => self.add_token_short(TokenType::Ampersand),
+            '|' => self.add_token_short(TokenType::Pipe),
+            '^' => self.add_token_short(TokenType::Caret),
+            '~' => self.add_token_short(TokenType::Tilde),
+            '%' => self.add_token_short(TokenType::Percent),
+            '?' => self.match_to_type('?', TokenType::Question, TokenType::QuestionQuestion),
             '!' => self.match_to_type('=', TokenType::Bang, TokenType::BangEqual),
-            '=' => self.match_to_type('=', TokenType::Equal, TokenType::EqualEqual),
-            '>' => self.match_to_type('=', TokenType::Greater, TokenType::GreaterEqual),
-            '<' => self.match_to_type('=', TokenType::Less, TokenType::LessEqual),
+            '=' => {
+                if self.match_char('>') {
+                    self.add_token_short(TokenType::FatArrow);
+                } else {
+                    self.match_to_type('=', TokenType::Equal, TokenType::EqualEqual);
+                }
+            },
+            '>' => {
+                if self.match_char('>') {
+                    self.add_token_short(TokenType::GreaterGreater);
+                } else {
+                    self.match_to_type('=', TokenType::Greater, TokenType::GreaterEqual);
+                }
+            },
+            '<' => {
+                if self.match_char('<') {
+                    self.add_token_short(TokenType::LessLess);
+                } else {
+                    self.match_to_type('=', TokenType::Less, TokenType::LessEqual);
+                }
+            },
             '/' => {
                 if self.match_char('/') {
                     while (self.peek()? != '\n') && (!self.is_at_end()) {
                         self.advance();
                     }
                 } else {
-                    self.add_token_short(TokenType::Slash);
+                    self.match_to_type('=', TokenType::Slash, TokenType::SlashEqual);
                 }
             }
 
@@ -193,9 +261,7 @@ impl<'a> Scanner<'a> {
                     return Ok(());
                 }
 
-                self.main.error(self.line, String::from(
-                    format!("Unexpected character \"{c}\".")
-                ));
+                self.main.error(self.line, format!("Unexpected character \"{c}\"."));
             },
         }
 
@@ -211,13 +277,9 @@ impl<'a> Scanner<'a> {
 
         let mut token_type: TokenType = TokenType::Identifier;
 
-        let lexeme: String = self.source.chars()
-            .skip(self.start)
-            .take(self.current - self.start)
-            .collect();
+        let lexeme: String = self.lexeme();
         let keywords: HashMap<String, TokenType> = get_keywords();
-        let keyword: Option<&TokenType> = keywords.get(&lexeme);
-        if keyword.is_some() { token_type = keyword.unwrap().clone(); }
+        if let Some(keyword) = keywords.get(&lexeme) { token_type = keyword.clone(); }
 
         self.add_token_short(token_type);
 
@@ -225,51 +287,278 @@ impl<'a> Scanner<'a> {
     }
 
     fn number(&mut self) -> Result<(), ScanError> {
-        let mut next_char: char = self.peek()?;
-        while self.is_digit(next_char) {
-            self.advance();
-            next_char = self.peek()?;
+        let first_char: char = self.source.get(self.start).copied().unwrap_or('\0');
+        if first_char == '0' {
+            let radix: Option<u32> = match self.peek()? {
+                'x' | 'X' => Some(16),
+                'o' | 'O' => Some(8),
+                'b' | 'B' => Some(2),
+                _ => None
+            };
+
+            if let Some(radix) = radix {
+                self.advance(); // consume the radix letter
+                return self.radix_number(radix);
+            }
         }
 
+        self.digits()?;
+
+        let mut is_float: bool = false;
         let peek_next: char = self.peek_next();
-        if next_char == '.' && self.is_digit(peek_next) {
+        if self.peek()? == '.' && self.is_digit(peek_next) {
             self.advance(); // consume the .
+            self.digits()?;
+            is_float = true;
+        }
 
-            next_char = self.peek()?;
-            while self.is_digit(next_char) {
+        if matches!(self.peek()?, 'e' | 'E') {
+            self.advance(); // consume the e
+            if matches!(self.peek()?, '+' | '-') {
                 self.advance();
-                next_char = self.peek()?;
             }
+
+            let next_char: char = self.peek()?;
+            if !self.is_digit(next_char) {
+                return self.number_error("Expected digits in exponent of number literal");
+            }
+
+            self.digits()?;
+            is_float = true;
         }
 
-        let string_literal: String = self.source.chars()
-            .skip(self.start)
-            .take(self.current - self.start)
-            .collect();
-        
-        let int_literal: f64 = string_literal.parse::<f64>().expect("Failed to parse string to i32");
+        let next_char: char = self.peek()?;
+        if self.is_alpha(next_char) {
+            return self.number_error("Invalid character in number literal");
+        }
+
+        let string_literal: String = self.lexeme();
+
+        // Separators must sit between digits, e.g. not `1_` or `1_.5`.
+        let misplaced_separator: bool = string_literal
+            .split(['.', 'e', 'E', '+', '-'])
+            .any(|part| part.starts_with('_') || part.ends_with('_'));
+        if misplaced_separator {
+            self.main.error(self.line, format!("Misplaced '_' in number literal '{string_literal}'."));
+            return Ok(());
+        }
+
+        let digits: String = string_literal.replace('_', "");
+
+        if !is_float {
+            match digits.parse::<i64>() {
+                Ok(int_literal) => self.add_token(TokenType::Number, Value::Int(int_literal)),
+                Err(_) => self.main.error(self.line, format!("Integer literal '{string_literal}' is too large.")),
+            }
 
-        self.add_token(TokenType::Number, Value::Number(int_literal));
+            return Ok(());
+        }
+
+        match digits.parse::<f64>() {
+            Ok(float_literal) => self.add_token(TokenType::Number, Value::Number(float_literal)),
+            Err(_) => self.main.error(self.line, format!("Invalid number literal '{string_literal}'.")),
+        }
+
+        Ok(())
+    }
+
+    // Consumes decimal digits along with any `_` separators.
+    fn digits(&mut self) -> Result<(), ScanError> {
+        let mut next_char: char = self.peek()?;
+        while self.is_digit(next_char) || next_char == '_' {
+            self.advance();
+            next_char = self.peek()?;
+        }
+
+        Ok(())
+    }
+
+    // Scans the digits of a `0x`, `0o` or `0b` literal, after the prefix.
+    fn radix_number(&mut self, radix: u32) -> Result<(), ScanError> {
+        let mut next_char: char = self.peek()?;
+        while self.is_alpha_numeric(next_char) {
+            self.advance();
+            next_char = self.peek()?;
+        }
+
+        let string_literal: String = self.lexeme();
+        let body: &str = &string_literal[2..];
+        let kind: &str = match radix {
+            16 => "hexadecimal",
+            8 => "octal",
+            _ => "binary"
+        };
+
+        if body.is_empty() {
+            self.main.error(self.line, format!("Expected digits after '{string_literal}' in {kind} literal."));
+            return Ok(());
+        }
+
+        if let Some(invalid) = body.chars().find(|c| *c != '_' && !c.is_digit(radix)) {
+            self.main.error(self.line, format!("Invalid digit '{invalid}' in {kind} literal '{string_literal}'."));
+            return Ok(());
+        }
+
+        if body.starts_with('_') || body.ends_with('_') {
+            self.main.error(self.line, format!("Misplaced '_' in number literal '{string_literal}'."));
+            return Ok(());
+        }
+
+        match i64::from_str_radix(&body.replace('_', ""), radix) {
+            Ok(int_literal) => self.add_token(TokenType::Number, Value::Int(int_literal)),
+            Err(_) => self.main.error(self.line, format!("Integer literal '{string_literal}' is too large.")),
+        }
+
+        Ok(())
+    }
+
+    // Skips the rest of a malformed literal so it is reported only once.
+    fn number_error(&mut self, message: &str) -> Result<(), ScanError> {
+        let mut next_char: char = self.peek()?;
+        while self.is_alpha_numeric(next_char) {
+            self.advance();
+            next_char = self.peek()?;
+        }
+
+        let string_literal: String = self.lexeme();
+        self.main.error(self.line, format!("{message} '{string_literal}'."));
 
         Ok(())
     }
 
     fn string(&mut self) -> Result<(), ScanError>  {
-        while self.peek()? != '"' && !self.is_at_end() {
-            if self.peek()? == '\n' { self.line += 1 }
+        if self.peek()? == '"' && self.peek_next() == '"' {
             self.advance();
+            self.advance();
+            return self.raw_string();
+        }
+
+        self.string_body()
+    }
+
+    // Reads up to the closing quote, or up to a `${` which hands control back
+    // to `scan_token` until the matching `}`.
+    fn string_body(&mut self) -> Result<(), ScanError> {
+        let mut string_literal: String = String::new();
+        while self.peek()? != '"' && !self.is_at_end() {
+            let c: char = self.advance();
+
+            match c {
+                '\\' => {
+                    if let Some(escaped) = self.escape()? {
+                        string_literal.push(escaped);
+                    }
+                },
+                '\n' => {
+                    self.line += 1;
+                    string_literal.push(c);
+                },
+                '$' if self.peek()? == '{' => {
+                    self.advance();
+                    self.interpolations.push(0);
+                    self.add_token(TokenType::Interpolation, Value::Str(string_literal));
+                    return Ok(());
+                },
+                _ => string_literal.push(c)
+            }
         }
 
         if self.is_at_end() {
             self.main.error(self.line, String::from("Unterminated string"));
+            return Ok(());
         }
 
         self.advance();
+        self.add_token(TokenType::String, Value::Str(string_literal));
+
+        Ok(())
+    }
+
+    // Translates the character after a backslash, reporting unknown escapes.
+    fn escape(&mut self) -> Result<Option<char>, ScanError> {
+        if self.is_at_end() {
+            return Ok(None);
+        }
+
+        let c: char = self.advance();
+        let escaped: char = match c {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '$' => '$',
+            'u' => return self.unicode_escape(),
+            _ => {
+                if c == '\n' { self.line += 1 }
+
+                self.main.error(self.line, format!("Unknown escape sequence '\\{}' in string.", c.escape_default()));
+                return Ok(None);
+            }
+        };
+
+        Ok(Some(escaped))
+    }
+
+    // Reads the `{XXXX}` part of a `\u{XXXX}` escape.
+    fn unicode_escape(&mut self) -> Result<Option<char>, ScanError> {
+        if !self.match_char('{') {
+            self.main.error(self.line, String::from("Expected '{' after '\\u' in string."));
+            return Ok(None);
+        }
+
+        let mut digits: String = String::new();
+        while self.peek()? != '}' && self.peek()? != '"' && !self.is_at_end() {
+            digits.push(self.advance());
+        }
+
+        if !self.match_char('}') {
+            self.main.error(self.line, String::from("Unterminated unicode escape in string."));
+            return Ok(None);
+        }
+
+        let code_point: Option<char> = if (1..=6).contains(&digits.len()) {
+            u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32)
+        } else {
+            None
+        };
 
-        let string_literal: String = self.source.chars()
-            .skip(self.start+1)
-            .take((self.current - self.start) - 2)
-            .collect();
+        if code_point.is_none() {
+            self.main.error(self.line, format!("Invalid unicode escape '\\u{{{digits}}}' in string."));
+        }
+
+        Ok(code_point)
+    }
+
+    // `"""..."""` strings take their contents verbatim, newlines included.
+    fn raw_string(&mut self) -> Result<(), ScanError> {
+        // A line break straight after the opening quotes is not part of the text.
+        if self.peek()? == '\n' {
+            self.line += 1;
+            self.advance();
+        }
+
+        let mut string_literal: String = String::new();
+        loop {
+            if self.is_at_end() {
+                self.main.error(self.line, String::from("Unterminated string"));
+                return Ok(());
+            }
+
+            if self.source[self.current..].starts_with(&['"', '"', '"']) {
+                break;
+            }
+
+            let c: char = self.advance();
+            if c == '\n' { self.line += 1 }
+            string_literal.push(c);
+        }
+
+        self.advance();
+        self.advance();
+        self.advance();
         self.add_token(TokenType::String, Value::Str(string_literal));
 
         Ok(())
@@ -280,13 +569,92 @@ impl<'a> Scanner<'a> {
             self.start = self.current;
             let result: Result<(), ScanError> = self.scan_token();
 
-            match result {
-                Err(err) => self.main.error(self.line, err.to_string()),
-                _ => ()
-            }
+            if let Err(err) = result { self.main.error(self.line, err.to_string()) }
+        }
+
+        if !self.interpolations.is_empty() {
+            self.main.error(self.line, String::from("Unterminated string interpolation"));
         }
         
         self.tokens.push(Token {token_type: TokenType::EOF, lexeme: "".to_string(), literal: Value::Null, line: self.line});
         self.tokens.clone()
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use crate::Sapphire;
+    use crate::token::Token;
+    use crate::token_type::TokenType;
+    use crate::value::Value;
+
+    use super::Scanner;
+
+    // Scans `source`, handing back its tokens and whether an error was reported.
+    fn scan(source: &str) -> (Vec<Token>, bool) {
+        let mut sapphire: Sapphire = Sapphire::new();
+        let tokens: Vec<Token> = Scanner::new(&mut sapphire, source.to_string()).scan_tokens();
+        (tokens, sapphire.had_error)
+    }
+
+    fn literal(source: &str) -> Value {
+        let (tokens, had_error) = scan(source);
+        assert!(!had_error, "unexpected error scanning {source:?}");
+        assert_eq!(tokens.len(), 2);
+        tokens[0].literal.clone()
+    }
+
+    #[test]
+    fn scans_number_literals() {
+        assert_eq!(literal("1_000_000"), Value::Int(1_000_000));
+        assert_eq!(literal("0xFF"), Value::Int(255));
+        assert_eq!(literal("0o17"), Value::Int(15));
+        assert_eq!(literal("0b1010_1010"), Value::Int(170));
+        assert_eq!(literal("1.5e3"), Value::Number(1500.0));
+        assert_eq!(literal("2E-2"), Value::Number(0.02));
+        assert_eq!(literal("9223372036854775807"), Value::Int(i64::MAX));
+    }
+
+    #[test]
+    fn reports_malformed_number_literals() {
+        for source in ["1_", "1__", "1_.5", "0x_F", "0xF_", "0x", "0b", "0b12", "1e", "1e+", "1.5eX", "12abc", "9223372036854775808", "0x8000000000000000"] {
+            let (tokens, had_error) = scan(source);
+            assert!(had_error, "expected an error scanning {source:?}");
+            assert!(!tokens.iter().any(|token| token.token_type == TokenType::Number), "{source:?} produced a number");
+        }
+    }
+
+    #[test]
+    fn scans_string_escapes() {
+        assert_eq!(literal(r#""a\tb\n\"c\"\$""#), Value::Str(String::from("a\tb\n\"c\"$")));
+        assert_eq!(literal(r#""\u{48}\u{1F600}""#), Value::Str(String::from("H\u{1F600}")));
+        assert_eq!(literal("\"h\u{e9}llo w\u{f6}rld\""), Value::Str(String::from("h\u{e9}llo w\u{f6}rld")));
+        assert_eq!(literal("\"\"\"\nraw \\n ${x}\n\"\"\""), Value::Str(String::from("raw \\n ${x}\n")));
+    }
+
+    #[test]
+    fn reports_malformed_strings() {
+        for source in [r#""\q""#, r#""\u{}""#, r#""\u{110000}""#, r#""\u{D800}""#, r#""\u{1234567}""#, r#""\u{zz}""#, r#""\u41""#, r#""\u{41""#, "\"abc", "\"\"\"abc\"\"", "\"a ${1 + 2}", "\"a ${1 + 2"] {
+            let (_, had_error) = scan(source);
+            assert!(had_error, "expected an error scanning {source:?}");
+        }
+    }
+
+    #[test]
+    fn scans_interpolated_strings() {
+        let (tokens, had_error) = scan(r#""a ${x} b ${ {1: 2}[1] } c""#);
+        assert!(!had_error);
+
+        let token_types: Vec<TokenType> = tokens.iter().map(|token| token.token_type.clone()).collect();
+        assert_eq!(token_types, vec![
+            TokenType::Interpolation, TokenType::Identifier,
+            TokenType::Interpolation, TokenType::LeftBrace, TokenType::Number, TokenType::Colon, TokenType::Number,
+            TokenType::RightBrace, TokenType::LeftBracket, TokenType::Number, TokenType::RightBracket,
+            TokenType::String, TokenType::EOF,
+        ]);
+        assert_eq!(tokens[0].literal, Value::Str(String::from("a ")));
+        assert_eq!(tokens[2].literal, Value::Str(String::from(" b ")));
+        assert_eq!(tokens[11].literal, Value::Str(String::from(" c")));
+    }
+}
\ No newline at end of file
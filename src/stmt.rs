@@ -1,41 +1,170 @@
+use std::rc::Rc;
 use crate::expr::Expr;
+use crate::pattern::Pattern;
 use crate::token::Token;
 #[derive(Clone)]
 pub enum Stmt {
+	Block {
+		statements: Vec<Stmt>,
+	},
+
+	Break {
+		keyword: Token,
+		label: Option<Token>,
+	},
+
+	Class {
+		name: Token,
+		superclass: Option<Expr>,
+		methods: Vec<Stmt>,
+	},
+
+	Const {
+		name: Token,
+		initializer: Expr,
+	},
+
+	Continue {
+		keyword: Token,
+		label: Option<Token>,
+	},
+
+	Destructure {
+		keyword: Token,
+		pattern: Pattern,
+		initializer: Expr,
+	},
+
 	Expression {
 		expression: Expr,
 	},
 
+	ForIn {
+		label: Option<Token>,
+		names: Vec<Token>,
+		keyword: Token,
+		iterable: Expr,
+		body: Box<Stmt>,
+	},
+
+	Function {
+		name: Token,
+		params: Vec<Token>,
+		body: Rc<Vec<Stmt>>,
+	},
+
+	If {
+		condition: Expr,
+		then_branch: Box<Stmt>,
+		else_branch: Option<Box<Stmt>>,
+	},
+
 	Print {
 		expression: Expr,
 	},
 
+	Return {
+		keyword: Token,
+		value: Option<Expr>,
+	},
+
+	Throw {
+		keyword: Token,
+		value: Expr,
+	},
+
+	Try {
+		body: Vec<Stmt>,
+		catch_name: Option<Token>,
+		catch_body: Vec<Stmt>,
+		finally_body: Vec<Stmt>,
+	},
+
 	Var {
 		name: Token,
 		initializer: Expr,
 	},
+
+	While {
+		label: Option<Token>,
+		condition: Expr,
+		body: Box<Stmt>,
+		increment: Option<Expr>,
+	},
 }
 
 pub trait Visitor {
 	type Result;
 
+	fn visit_block(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_break(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_class(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_const(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_continue(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_destructure(&mut self, stmt: &Stmt) -> Self::Result;
 	fn visit_expression(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_forin(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_function(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_if(&mut self, stmt: &Stmt) -> Self::Result;
 	fn visit_print(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_return(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_throw(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_try(&mut self, stmt: &Stmt) -> Self::Result;
 	fn visit_var(&mut self, stmt: &Stmt) -> Self::Result;
+	fn visit_while(&mut self, stmt: &Stmt) -> Self::Result;
 }
 
 impl Stmt {
 	pub fn accept<V: Visitor>(&self, visitor: &mut V) -> V::Result {
 		match self {
+			Stmt::Block {statements: _,  } => {
+				visitor.visit_block(self)
+			}
+			Stmt::Break {keyword: _, label: _,  } => {
+				visitor.visit_break(self)
+			}
+			Stmt::Class {name: _, superclass: _, methods: _,  } => {
+				visitor.visit_class(self)
+			}
+			Stmt::Const {name: _, initializer: _,  } => {
+				visitor.visit_const(self)
+			}
+			Stmt::Continue {keyword: _, label: _,  } => {
+				visitor.visit_continue(self)
+			}
+			Stmt::Destructure {keyword: _, pattern: _, initializer: _,  } => {
+				visitor.visit_destructure(self)
+			}
 			Stmt::Expression {expression: _,  } => {
 				visitor.visit_expression(self)
 			}
+			Stmt::ForIn {label: _, names: _, keyword: _, iterable: _, body: _,  } => {
+				visitor.visit_forin(self)
+			}
+			Stmt::Function {name: _, params: _, body: _,  } => {
+				visitor.visit_function(self)
+			}
+			Stmt::If {condition: _, then_branch: _, else_branch: _,  } => {
+				visitor.visit_if(self)
+			}
 			Stmt::Print {expression: _,  } => {
 				visitor.visit_print(self)
 			}
+			Stmt::Return {keyword: _, value: _,  } => {
+				visitor.visit_return(self)
+			}
+			Stmt::Throw {keyword: _, value: _,  } => {
+				visitor.visit_throw(self)
+			}
+			Stmt::Try {body: _, catch_name: _, catch_body: _, finally_body: _,  } => {
+				visitor.visit_try(self)
+			}
 			Stmt::Var {name: _, initializer: _,  } => {
 				visitor.visit_var(self)
 			}
+			Stmt::While {label: _, condition: _, body: _, increment: _,  } => {
+				visitor.visit_while(self)
+			}
 		}
 	}
 }
\ No newline at end of file
@@ -1,21 +1,33 @@
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TokenType {
     // Single-character tokens.
-    LeftParen, RightParen, LeftBrace, RightBrace,
-    Comma, Dot, DoubleDot, Minus, Plus, Semicolon, Slash, Star,
+    LeftParen, RightParen, LeftBrace, RightBrace, LeftBracket, RightBracket,
+    Colon, Comma, Dot, DoubleDot, Percent, Semicolon,
+
+    Ampersand, Caret, Pipe, Tilde,
 
     // One or two character tokens.
     Bang, BangEqual,
-    Equal, EqualEqual,
-    Greater, GreaterEqual,
-    Less, LessEqual,
+    Equal, EqualEqual, FatArrow,
+    Greater, GreaterEqual, GreaterGreater,
+    Less, LessEqual, LessLess,
+    Star, StarStar,
+    Minus, MinusEqual, MinusMinus,
+    Plus, PlusEqual, PlusPlus,
+    Slash, SlashEqual,
+    StarEqual, DoubleDotEqual, DoubleDotLess,
+    Question, QuestionQuestion,
 
     // Literals.
     Identifier, String, Number,
+    // A string segment that ends at `${`, followed by the tokens of the
+    // embedded expression.
+    Interpolation,
 
     // Keywords.
-    And, Class, Else, False, Fn, For, If, Nil, Or,
-    Print, Return, Super, This, True, Var, While,
+    And, Break, Catch, Class, Const, Continue, Else, False, Finally, Fn, For, If, In, Match, Nil, Or,
+    Print, Return, Super, This, Throw, True, Try, Var, While,
 
     EOF
 }
\ No newline at end of file
@@ -1,18 +1,107 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::class::{Class, Instance};
+use crate::function::Function;
+use crate::map::Map;
+use crate::native::NativeFunction;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Str(String),
+    Int(i64),
     Number(f64),
     Bool(bool),
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<Map>>),
+    Function(Rc<Function>),
+    Native(Rc<NativeFunction>),
+    Class(Rc<Class>),
+    Instance(Rc<RefCell<Instance>>),
+    // A half-open `start..<end` range of Ints.
+    Range(i64, i64),
     Null
 }
 
 impl Value {
-    pub fn to_string(&self) -> String {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Str(_) => "Str",
+            Value::Int(_) => "Int",
+            Value::Number(_) => "Number",
+            Value::Bool(_) => "Bool",
+            Value::List(_) => "List",
+            Value::Map(_) => "Map",
+            Value::Function(_) | Value::Native(_) => "Function",
+            Value::Class(_) => "Class",
+            Value::Instance(_) => "Instance",
+            Value::Range(_, _) => "Range",
+            Value::Null => "Null",
+        }
+    }
+
+    // Quotes strings inside collections so `["1"]` and `[1]` print differently.
+    fn fmt_nested(&self, f: &mut fmt::Formatter, seen: &mut Vec<*const ()>) -> fmt::Result {
         match self {
-            Value::Str(str) => str.clone(),
-            Value::Number(num) => num.to_string(),
-            Value::Bool(bool) => bool.to_string(),
-            Value::Null => "nil".to_string(),
+            Value::Str(str) => write!(f, "{:?}", str),
+            _ => self.fmt_seen(f, seen)
         }
     }
+
+    // `seen` holds the collections currently being printed, so one that
+    // contains itself prints as `[...]` or `{...}` instead of recursing forever.
+    fn fmt_seen(&self, f: &mut fmt::Formatter, seen: &mut Vec<*const ()>) -> fmt::Result {
+        match self {
+            Value::Str(str) => write!(f, "{}", str),
+            Value::Int(int) => write!(f, "{}", int),
+            // Debug formatting keeps the fraction, so 1.0 never prints as 1.
+            Value::Number(num) => write!(f, "{:?}", num),
+            Value::Bool(bool) => write!(f, "{}", bool),
+            Value::List(list) => {
+                let pointer: *const () = Rc::as_ptr(list) as *const ();
+                if seen.contains(&pointer) {
+                    return write!(f, "[...]");
+                }
+
+                seen.push(pointer);
+                write!(f, "[")?;
+                for (i, element) in list.borrow().iter().enumerate() {
+                    if i > 0 { write!(f, ", ")?; }
+                    element.fmt_nested(f, seen)?;
+                }
+                seen.pop();
+                write!(f, "]")
+            },
+            Value::Map(map) => {
+                let pointer: *const () = Rc::as_ptr(map) as *const ();
+                if seen.contains(&pointer) {
+                    return write!(f, "{{...}}");
+                }
+
+                seen.push(pointer);
+                write!(f, "{{")?;
+                for (i, (key, value)) in map.borrow().iter().enumerate() {
+                    if i > 0 { write!(f, ", ")?; }
+                    key.to_value().fmt_nested(f, seen)?;
+                    write!(f, ": ")?;
+                    value.fmt_nested(f, seen)?;
+                }
+                seen.pop();
+                write!(f, "}}")
+            },
+            Value::Function(function) => write!(f, "{:?}", function),
+            Value::Native(native) => write!(f, "{:?}", native),
+            Value::Class(class) => write!(f, "{:?}", class),
+            Value::Instance(instance) => write!(f, "{:?}", instance.borrow()),
+            Value::Range(start, end) => write!(f, "{}..<{}", start, end),
+            Value::Null => write!(f, "nil"),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_seen(f, &mut vec![])
+    }
 }
\ No newline at end of file
@@ -2,15 +2,13 @@ use std::env;
 use std::io::{self, Write};
 use std::fs;
 
-fn define_ast(output_directory: String, base_name: String, types: Vec<String>) -> io::Result<()> {
+fn define_ast(output_directory: String, base_name: String, imports: Vec<String>, types: Vec<String>) -> io::Result<()> {
     let mut path: String = format!("{}/{}.rs", output_directory, base_name.to_lowercase());
 
-    let mut ast_string: String = String::from(
-        "use crate::token::Token;\n\
-        use crate::value::Value;\n\
-        \n\
-        "
-    );
+    let mut ast_string: String = String::new();
+    for import in &imports {
+        ast_string += format!("use {};\n", import).as_str();
+    }
 
     ast_string += format!("#[derive(Clone)]\npub enum {} {{", base_name).as_str();
     
@@ -54,7 +52,7 @@ fn define_ast(output_directory: String, base_name: String, types: Vec<String>) -
         \ttype Result;\n"
     );
     for struct_name in &struct_names {
-        visitor_string += format!("\n\tfn visit_{}(&mut self, {}: &{}) -> Self::Result;", struct_name.to_lowercase(), struct_name.to_lowercase(), base_name).as_str();
+        visitor_string += format!("\n\tfn visit_{}(&mut self, {}: &{}) -> Self::Result;", struct_name.to_lowercase(), base_name.to_lowercase(), base_name).as_str();
     }
     visitor_string += "\n}\n\n";
     ast_string += visitor_string.as_str();
@@ -80,8 +78,8 @@ fn define_ast(output_directory: String, base_name: String, types: Vec<String>) -
             .collect();
         
         let mut visitor_impl_string = format!(
-            "\t\t\tExpr::{} {{",
-        struct_name);
+            "\t\t\t{}::{} {{",
+        base_name, struct_name);
 
         for struct_param in struct_params {
             let param_split: Vec<String> = struct_param
@@ -89,7 +87,7 @@ fn define_ast(output_directory: String, base_name: String, types: Vec<String>) -
                 .map(|s| s.to_string())
                 .collect();
             
-            visitor_impl_string += format!("{}, ", param_split[1]).as_str();
+            visitor_impl_string += format!("{}: _, ", param_split[1]).as_str();
         }
             
         visitor_impl_string += format!(
@@ -104,8 +102,9 @@ fn define_ast(output_directory: String, base_name: String, types: Vec<String>) -
     ast_string += "\t\t}\n\t}\n}";
 
     let mut file = fs::OpenOptions::new()
-        .write(true) // Enable append mode
+        .write(true) // Enable write mode
         .create(true) // Create the file if it doesn't exist
+        .truncate(true) // Overwrite the previously generated file
         .open(path)?;
 
     file.write_all(&ast_string.into_bytes());
@@ -122,10 +121,58 @@ fn main() {
     }
 
     let output_dir: String = args[1].clone();
-    define_ast(output_dir, "Expr".to_string(), vec![
+    define_ast(output_dir.clone(), "Expr".to_string(), vec![
+        String::from("std::cell::Cell"),
+        String::from("std::rc::Rc"),
+        String::from("crate::pattern::MatchArm"),
+        String::from("crate::stmt::Stmt"),
+        String::from("crate::token::Token"),
+        String::from("crate::value::Value"),
+    ], vec![
+        String::from("Assign     :Token;name,Box<Expr>;value,Cell<Option<usize>>;depth"),
         String::from("Binary     :Box<Expr>;left,Token;operator,Box<Expr>;right"),
+        String::from("Call       :Box<Expr>;callee,Token;paren,Vec<Expr>;arguments"),
+        String::from("Compound   :Box<Expr>;target,Token;operator,Box<Expr>;value,bool;postfix"),
+        String::from("Conditional :Box<Expr>;condition,Box<Expr>;then_branch,Box<Expr>;else_branch"),
+        String::from("Get        :Box<Expr>;object,Token;name"),
         String::from("Grouping   :Box<Expr>;expression"),
+        String::from("Index      :Box<Expr>;object,Token;bracket,Box<Expr>;index"),
+        String::from("IndexSet   :Box<Expr>;object,Token;bracket,Box<Expr>;index,Box<Expr>;value"),
+        String::from("Interpolation :Vec<Expr>;parts"),
+        String::from("Lambda     :Token;keyword,Vec<Token>;params,Rc<Vec<Stmt>>;body"),
+        String::from("List       :Vec<Expr>;elements"),
         String::from("Literal    :Value;value"),
+        String::from("Map        :Token;brace,Vec<Expr>;keys,Vec<Expr>;values"),
+        String::from("Match      :Token;keyword,Box<Expr>;subject,Vec<MatchArm>;arms"),
+        String::from("Logical    :Box<Expr>;left,Token;operator,Box<Expr>;right"),
+        String::from("Set        :Box<Expr>;object,Token;name,Box<Expr>;value"),
+        String::from("Super      :Token;keyword,Token;method,Cell<Option<usize>>;depth"),
+        String::from("This       :Token;keyword,Cell<Option<usize>>;depth"),
         String::from("Unary      :Token;operator,Box<Expr>;right"),
+        String::from("Variable   :Token;name,Cell<Option<usize>>;depth"),
     ]);
-}
\ No newline at end of file
+
+    define_ast(output_dir, "Stmt".to_string(), vec![
+        String::from("std::rc::Rc"),
+        String::from("crate::expr::Expr"),
+        String::from("crate::pattern::Pattern"),
+        String::from("crate::token::Token"),
+    ], vec![
+        String::from("Block      :Vec<Stmt>;statements"),
+        String::from("Break      :Token;keyword,Option<Token>;label"),
+        String::from("Class      :Token;name,Option<Expr>;superclass,Vec<Stmt>;methods"),
+        String::from("Const      :Token;name,Expr;initializer"),
+        String::from("Continue   :Token;keyword,Option<Token>;label"),
+        String::from("Destructure :Token;keyword,Pattern;pattern,Expr;initializer"),
+        String::from("Expression :Expr;expression"),
+        String::from("ForIn      :Option<Token>;label,Vec<Token>;names,Token;keyword,Expr;iterable,Box<Stmt>;body"),
+        String::from("Function   :Token;name,Vec<Token>;params,Rc<Vec<Stmt>>;body"),
+        String::from("If         :Expr;condition,Box<Stmt>;then_branch,Option<Box<Stmt>>;else_branch"),
+        String::from("Print      :Expr;expression"),
+        String::from("Return     :Token;keyword,Option<Expr>;value"),
+        String::from("Throw      :Token;keyword,Expr;value"),
+        String::from("Try        :Vec<Stmt>;body,Option<Token>;catch_name,Vec<Stmt>;catch_body,Vec<Stmt>;finally_body"),
+        String::from("Var        :Token;name,Expr;initializer"),
+        String::from("While      :Option<Token>;label,Expr;condition,Box<Stmt>;body,Option<Expr>;increment"),
+    ]);
+}